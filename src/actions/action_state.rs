@@ -0,0 +1,36 @@
+/// The state of a button-like action, such as jumping or activating the shield.
+///
+/// Anything that drives the player, be it devices, recorded input or AI, feeds it
+/// through `ActionState::update` once per frame.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ActionState {
+    pressed: bool,
+    just_pressed: bool,
+    held_seconds: f32,
+}
+
+impl ActionState {
+    pub fn update(&mut self, pressed: bool, delta_seconds: f32) {
+        self.just_pressed = pressed && !self.pressed;
+        self.held_seconds = match (pressed, self.pressed) {
+            (true, true) => self.held_seconds + delta_seconds,
+            _ => 0.,
+        };
+        self.pressed = pressed;
+    }
+
+    pub fn pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.just_pressed
+    }
+
+    /// How long the action has been pressed without interruption, zero if released.
+    // Nothing in the game reads it yet, but it's there for what drives the player
+    #[allow(dead_code)]
+    pub fn held_seconds(&self) -> f32 {
+        self.held_seconds
+    }
+}
@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::actions::game_control::GameControl;
+use crate::persistence::Persistent;
+
+/// A single key or gamepad button that can trigger a `GameControl`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    GamepadButton(GamepadButtonType),
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::GamepadButton(button) => write!(f, "Pad {button:?}"),
+        }
+    }
+}
+
+/// Which keys and gamepad buttons trigger each `GameControl`.
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct InputBindings(BTreeMap<GameControl, Vec<Binding>>);
+
+impl Persistent for InputBindings {
+    const KEY: &'static str = "input_bindings";
+    const VERSION: u32 = 1;
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Binding::*;
+        use GamepadButtonType::*;
+
+        Self(BTreeMap::from([
+            (
+                GameControl::Left,
+                vec![Key(KeyCode::A), Key(KeyCode::Left), GamepadButton(DPadLeft)],
+            ),
+            (
+                GameControl::Right,
+                vec![
+                    Key(KeyCode::D),
+                    Key(KeyCode::Right),
+                    GamepadButton(DPadRight),
+                ],
+            ),
+            (
+                GameControl::Jump,
+                vec![
+                    Key(KeyCode::W),
+                    Key(KeyCode::Up),
+                    GamepadButton(South),
+                    GamepadButton(DPadUp),
+                ],
+            ),
+            (
+                GameControl::Drop,
+                vec![Key(KeyCode::S), Key(KeyCode::Down), GamepadButton(DPadDown)],
+            ),
+            (
+                GameControl::Shield,
+                vec![
+                    Key(KeyCode::Space),
+                    GamepadButton(RightTrigger2),
+                    GamepadButton(RightTrigger),
+                ],
+            ),
+            (
+                GameControl::Pause,
+                vec![Key(KeyCode::Escape), GamepadButton(Start)],
+            ),
+        ]))
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, control: GameControl) -> &[Binding] {
+        self.0.get(&control).map_or(&[], Vec::as_slice)
+    }
+
+    /// Binds `binding` to `control`, replacing the binding at `index` if there is one.
+    ///
+    /// Returns the control the binding was taken from if it was already in use, since
+    /// a binding can only trigger a single control.
+    pub fn bind(
+        &mut self,
+        control: GameControl,
+        index: usize,
+        binding: Binding,
+    ) -> Option<GameControl> {
+        let conflict = self.unbind_everywhere(control, binding);
+
+        let bindings = self.0.entry(control).or_default();
+        let index = index.min(bindings.len());
+        if index < bindings.len() {
+            bindings[index] = binding;
+        } else {
+            bindings.push(binding);
+        }
+        // The binding moves to `index` if the control already had it elsewhere
+        let mut slot = 0;
+        bindings.retain(|other| {
+            let keep = slot == index || *other != binding;
+            slot += 1;
+            keep
+        });
+
+        conflict
+    }
+
+    pub fn unbind(&mut self, control: GameControl, index: usize) {
+        if let Some(bindings) = self.0.get_mut(&control) {
+            if index < bindings.len() {
+                bindings.remove(index);
+            }
+        }
+    }
+
+    fn unbind_everywhere(&mut self, except: GameControl, binding: Binding) -> Option<GameControl> {
+        let mut conflict = None;
+        for (control, bindings) in self.0.iter_mut() {
+            if *control == except {
+                continue;
+            }
+            if let Some(index) = bindings.iter().position(|other| *other == binding) {
+                bindings.remove(index);
+                conflict = Some(*control);
+            }
+        }
+        conflict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binding_a_key_in_use_takes_it_from_the_other_control() {
+        let mut bindings = InputBindings::default();
+        let conflict = bindings.bind(GameControl::Jump, 0, Binding::Key(KeyCode::A));
+        assert_eq!(conflict, Some(GameControl::Left));
+        assert_eq!(
+            bindings.get(GameControl::Left),
+            &[
+                Binding::Key(KeyCode::Left),
+                Binding::GamepadButton(GamepadButtonType::DPadLeft)
+            ]
+        );
+        assert_eq!(bindings.get(GameControl::Jump)[0], Binding::Key(KeyCode::A));
+    }
+
+    #[test]
+    fn rebinding_a_key_to_its_own_slot_changes_nothing() {
+        let mut bindings = InputBindings::default();
+        let conflict = bindings.bind(GameControl::Jump, 0, Binding::Key(KeyCode::W));
+        assert_eq!(conflict, None);
+        assert_eq!(
+            bindings.get(GameControl::Jump),
+            InputBindings::default().get(GameControl::Jump)
+        );
+    }
+
+    #[test]
+    fn rebinding_a_key_to_another_slot_moves_it() {
+        let mut bindings = InputBindings::default();
+        bindings.bind(GameControl::Jump, 1, Binding::Key(KeyCode::W));
+        assert_eq!(
+            bindings.get(GameControl::Jump),
+            &[
+                Binding::Key(KeyCode::W),
+                Binding::GamepadButton(GamepadButtonType::South),
+                Binding::GamepadButton(GamepadButtonType::DPadUp)
+            ]
+        );
+    }
+
+    #[test]
+    fn binding_past_the_end_adds_a_binding() {
+        let mut bindings = InputBindings::default();
+        bindings.bind(GameControl::Pause, 5, Binding::Key(KeyCode::P));
+        assert_eq!(bindings.get(GameControl::Pause).len(), 3);
+        assert_eq!(
+            bindings.get(GameControl::Pause)[2],
+            Binding::Key(KeyCode::P)
+        );
+    }
+}
@@ -1,33 +1,49 @@
 use bevy::prelude::{Input, KeyCode, Res};
+use serde::{Deserialize, Serialize};
 
+use crate::actions::bindings::{Binding, InputBindings};
+use crate::actions::gamepad_input::GamepadInput;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum GameControl {
-    Up,
-    Down,
     Left,
     Right,
+    Jump,
+    Drop,
+    Shield,
+    Pause,
 }
 
 impl GameControl {
-    pub fn pressed(&self, keyboard_input: &Res<Input<KeyCode>>) -> bool {
-        match self {
-            GameControl::Up => {
-                keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up)
-            }
-            GameControl::Down => {
-                keyboard_input.pressed(KeyCode::S) || keyboard_input.pressed(KeyCode::Down)
-            }
-            GameControl::Left => {
-                keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left)
-            }
-            GameControl::Right => {
-                keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right)
-            }
-        }
+    pub const ALL: [GameControl; 6] = [
+        GameControl::Left,
+        GameControl::Right,
+        GameControl::Jump,
+        GameControl::Drop,
+        GameControl::Shield,
+        GameControl::Pause,
+    ];
+
+    pub fn pressed(
+        &self,
+        bindings: &InputBindings,
+        keyboard_input: &Res<Input<KeyCode>>,
+        gamepad_input: &GamepadInput,
+    ) -> bool {
+        bindings.get(*self).iter().any(|binding| match binding {
+            Binding::Key(key) => keyboard_input.pressed(*key),
+            Binding::GamepadButton(button) => gamepad_input.pressed(*button),
+        })
     }
 }
 
-pub fn get_movement(control: GameControl, input: &Res<Input<KeyCode>>) -> f32 {
-    if control.pressed(input) {
+pub fn get_movement(
+    control: GameControl,
+    bindings: &InputBindings,
+    input: &Res<Input<KeyCode>>,
+    gamepad_input: &GamepadInput,
+) -> f32 {
+    if control.pressed(bindings, input, gamepad_input) {
         1.0
     } else {
         0.0
@@ -0,0 +1,41 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Reads the state of all connected gamepads, so any of them can control the game.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl GamepadInput<'_> {
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.gamepads.iter().any(|gamepad| {
+            self.buttons
+                .pressed(GamepadButton::new(gamepad, button_type))
+        })
+    }
+
+    /// The first button pressed this frame on any gamepad.
+    pub fn first_just_pressed(&self) -> Option<GamepadButtonType> {
+        self.buttons
+            .get_just_pressed()
+            .map(|button| button.button_type)
+            .next()
+    }
+
+    /// The axis value of whichever gamepad is tilted the furthest.
+    pub fn axis(&self, axis_type: GamepadAxisType) -> f32 {
+        self.gamepads
+            .iter()
+            .filter_map(|gamepad| self.axes.get(GamepadAxis::new(gamepad, axis_type)))
+            .fold(0., |value: f32, other| {
+                if other.abs() > value.abs() {
+                    other
+                } else {
+                    value
+                }
+            })
+    }
+}
@@ -1,39 +1,100 @@
 use bevy::prelude::*;
 
-use crate::actions::game_control::{get_movement, GameControl};
+use crate::actions::game_control::get_movement;
 use crate::app_state::GameState;
+use crate::persistence::PersistenceAppExt;
 
+mod action_state;
+mod bindings;
 mod game_control;
+mod gamepad_input;
+
+pub use action_state::ActionState;
+pub use bindings::{Binding, InputBindings};
+pub use game_control::GameControl;
+pub use gamepad_input::GamepadInput;
 
 pub struct ActionsPlugin;
 
-// This plugin listens for keyboard input and converts the input into Actions
+// This plugin listens for keyboard and gamepad input and converts the input into Actions
 // Actions can then be used as a resource in other systems to act on the player input.
 impl Plugin for ActionsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Actions>().add_systems(
-            Update,
-            set_movement_actions.run_if(in_state(GameState::Playing)),
-        );
+        app.init_resource::<Actions>()
+            .init_persistent_resource::<InputBindings>()
+            .add_systems(
+                Update,
+                set_actions
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+            );
     }
 }
 
 #[derive(Default, Resource)]
 pub struct Actions {
     pub player_movement: Option<Vec2>,
+    pub jump: ActionState,
+    pub shield: ActionState,
+    pub pause: ActionState,
 }
 
-pub fn set_movement_actions(mut actions: ResMut<Actions>, keyboard_input: Res<Input<KeyCode>>) {
-    let player_movement = Vec2::new(
-        get_movement(GameControl::Right, &keyboard_input)
-            - get_movement(GameControl::Left, &keyboard_input),
-        get_movement(GameControl::Up, &keyboard_input)
-            - get_movement(GameControl::Down, &keyboard_input),
+/// How far the left stick needs to be pushed down to count as `GameControl::Drop`.
+const STICK_DROP_THRESHOLD: f32 = -0.5;
+
+pub fn set_actions(
+    time: Res<Time>,
+    mut actions: ResMut<Actions>,
+    bindings: Res<InputBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: GamepadInput,
+) {
+    let digital_movement = Vec2::new(
+        get_movement(
+            GameControl::Right,
+            &bindings,
+            &keyboard_input,
+            &gamepad_input,
+        ) - get_movement(
+            GameControl::Left,
+            &bindings,
+            &keyboard_input,
+            &gamepad_input,
+        ),
+        get_movement(
+            GameControl::Jump,
+            &bindings,
+            &keyboard_input,
+            &gamepad_input,
+        ) - get_movement(
+            GameControl::Drop,
+            &bindings,
+            &keyboard_input,
+            &gamepad_input,
+        ),
     );
+    let stick_y = gamepad_input.axis(GamepadAxisType::LeftStickY);
+    let analog_movement = Vec2::new(
+        gamepad_input.axis(GamepadAxisType::LeftStickX),
+        if stick_y < STICK_DROP_THRESHOLD {
+            -1.
+        } else {
+            0.
+        },
+    );
+    let player_movement = (digital_movement + analog_movement)
+        .clamp(Vec2::NEG_ONE, Vec2::ONE)
+        .clamp_length_max(1.);
 
     if player_movement != Vec2::ZERO {
-        actions.player_movement = Some(player_movement.normalize());
+        actions.player_movement = Some(player_movement);
     } else {
         actions.player_movement = None;
     }
+
+    let delta = time.delta_seconds();
+    let pressed =
+        |control: GameControl| control.pressed(&bindings, &keyboard_input, &gamepad_input);
+    actions.jump.update(pressed(GameControl::Jump), delta);
+    actions.shield.update(pressed(GameControl::Shield), delta);
+    actions.pause.update(pressed(GameControl::Pause), delta);
 }
@@ -13,7 +13,21 @@ pub enum GameState {
     #[default]
     None,
     Playing,
+    Paused,
+    /// Passed through on the way back to `Playing` to restart the level.
+    Restarting,
     GameOver,
+    LevelComplete,
+}
+
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum MenuState {
+    #[default]
+    None,
+    Main,
+    LevelSelect,
+    Controls,
+    Sound,
 }
 
 pub struct AppStatePlugin;
@@ -22,8 +36,12 @@ impl Plugin for AppStatePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<AppState>()
             .add_state::<GameState>()
+            .add_state::<MenuState>()
             .add_systems(OnEnter(AppState::InGame), set_game_state_playing)
-            .add_systems(OnExit(AppState::InGame), set_game_state_none);
+            .add_systems(OnExit(AppState::InGame), set_game_state_none)
+            .add_systems(OnEnter(GameState::Restarting), set_game_state_playing)
+            .add_systems(OnEnter(AppState::Menu), set_menu_state_main)
+            .add_systems(OnExit(AppState::Menu), set_menu_state_none);
     }
 }
 
@@ -34,3 +52,11 @@ fn set_game_state_playing(mut playing_state: ResMut<NextState<GameState>>) {
 fn set_game_state_none(mut playing_state: ResMut<NextState<GameState>>) {
     playing_state.set(GameState::None);
 }
+
+fn set_menu_state_main(mut menu_state: ResMut<NextState<MenuState>>) {
+    menu_state.set(MenuState::Main);
+}
+
+fn set_menu_state_none(mut menu_state: ResMut<NextState<MenuState>>) {
+    menu_state.set(MenuState::None);
+}
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Parses the value following `name` on the command line, e.g. `--seed 42`.
+pub fn arg_value<T>(name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut args = std::env::args().skip_while(|arg| arg != name).skip(1);
+    let arg = args.next()?;
+    match arg.parse() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Ignoring invalid value \"{arg}\" for {name}: {error}");
+            None
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::app_state::*;
+use crate::loading::AudioAssets;
+use crate::player::Player;
+use crate::rain::*;
+use crate::settings::AudioSettings;
+use crate::shield::Shield;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_kira_audio::prelude::*;
+use std::time::Duration;
+
+pub struct InternalAudioPlugin;
+
+/// Plays the rain ambience, the sounds of drops hitting the player and their
+/// shield, and the menu music.
+impl Plugin for InternalAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AudioPlugin)
+            .add_audio_channel::<MusicChannel>()
+            .add_audio_channel::<RainChannel>()
+            .init_resource::<RainIntensity>()
+            .init_resource::<Volumes>()
+            .add_systems(PreUpdate, update_volumes)
+            .add_systems(
+                Update,
+                apply_music_volume.run_if(resource_changed::<Volumes>()),
+            )
+            .add_systems(OnEnter(AppState::Menu), play_menu_music)
+            .add_systems(OnExit(AppState::Menu), stop_menu_music)
+            .add_systems(OnEnter(AppState::InGame), start_rain_bed)
+            .add_systems(OnExit(AppState::InGame), stop_rain_bed)
+            .add_systems(OnEnter(GameState::Paused), pause_rain_bed)
+            .add_systems(OnExit(GameState::Paused), resume_rain_bed)
+            .add_systems(
+                Update,
+                (measure_rain, play_hit_sounds).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                follow_rain
+                    .after(measure_rain)
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct MusicChannel;
+
+#[derive(Resource)]
+struct RainChannel;
+
+/// How loud music and sound effects are played, from the `AudioSettings` and
+/// whether the window is in focus.
+#[derive(Resource, PartialEq)]
+pub struct Volumes {
+    pub music: f64,
+    pub sfx: f64,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self { music: 1., sfx: 1. }
+    }
+}
+
+/// How heavily it is raining, from 0 to 1, judged by the number of drops
+/// falling.
+#[derive(Resource, Default)]
+pub struct RainIntensity(pub f32);
+
+impl RainIntensity {
+    /// How many drops it takes for the rain to be as heavy as it gets.
+    const FULL_DROPS: f32 = 400.;
+}
+
+/// The looping rain ambience, which gets louder the more drops are falling.
+#[derive(Resource)]
+struct RainBed {
+    instance: Handle<AudioInstance>,
+    volume: f64,
+}
+
+impl RainBed {
+    const MAX_VOLUME: f64 = 0.6;
+    /// How quickly the volume follows changes in how heavily it rains.
+    const FOLLOW_SPEED: f64 = 2.;
+}
+
+const MUSIC_VOLUME: f64 = 0.5;
+const FADE_OUT: Duration = Duration::from_millis(500);
+
+fn update_volumes(
+    settings: Res<AudioSettings>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut volumes: ResMut<Volumes>,
+) {
+    let focused = window_query.iter().all(|window| window.focused);
+    let master = if focused || !settings.mute_on_focus_loss {
+        settings.master as f64
+    } else {
+        0.
+    };
+    let new_volumes = Volumes {
+        music: master * settings.music as f64,
+        sfx: master * settings.sfx as f64,
+    };
+    // Only touched when different, to not trigger change detection every frame
+    if *volumes != new_volumes {
+        *volumes = new_volumes;
+    }
+}
+
+fn apply_music_volume(music: Res<AudioChannel<MusicChannel>>, volumes: Res<Volumes>) {
+    music.set_volume(MUSIC_VOLUME * volumes.music);
+}
+
+fn play_menu_music(
+    music: Res<AudioChannel<MusicChannel>>,
+    audio_assets: Res<AudioAssets>,
+    volumes: Res<Volumes>,
+) {
+    music
+        .play(audio_assets.menu.clone())
+        .looped()
+        .with_volume(MUSIC_VOLUME * volumes.music);
+}
+
+fn stop_menu_music(music: Res<AudioChannel<MusicChannel>>) {
+    music.stop().fade_out(AudioTween::linear(FADE_OUT));
+}
+
+fn start_rain_bed(
+    mut commands: Commands,
+    rain_channel: Res<AudioChannel<RainChannel>>,
+    audio_assets: Res<AudioAssets>,
+) {
+    let instance = rain_channel
+        .play(audio_assets.rain.clone())
+        .looped()
+        .with_volume(0.)
+        .handle();
+    commands.insert_resource(RainBed {
+        instance,
+        volume: 0.,
+    });
+}
+
+fn stop_rain_bed(mut commands: Commands, rain_channel: Res<AudioChannel<RainChannel>>) {
+    rain_channel.stop().fade_out(AudioTween::linear(FADE_OUT));
+    commands.remove_resource::<RainBed>();
+}
+
+fn pause_rain_bed(rain_channel: Res<AudioChannel<RainChannel>>) {
+    rain_channel.pause();
+}
+
+fn resume_rain_bed(rain_channel: Res<AudioChannel<RainChannel>>) {
+    rain_channel.resume();
+}
+
+fn measure_rain(rain_query: Query<(), With<Rain>>, mut rain_intensity: ResMut<RainIntensity>) {
+    rain_intensity.0 = (rain_query.iter().count() as f32 / RainIntensity::FULL_DROPS).min(1.);
+}
+
+fn follow_rain(
+    time: Res<Time>,
+    rain_intensity: Res<RainIntensity>,
+    volumes: Res<Volumes>,
+    rain_bed: Option<ResMut<RainBed>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(mut rain_bed) = rain_bed else {
+        return;
+    };
+    let target = rain_intensity.0 as f64 * RainBed::MAX_VOLUME;
+    let follow = (RainBed::FOLLOW_SPEED * time.delta_seconds_f64()).min(1.);
+    rain_bed.volume += (target - rain_bed.volume) * follow;
+
+    if let Some(instance) = audio_instances.get_mut(&rain_bed.instance) {
+        instance.set_volume(rain_bed.volume * volumes.sfx, AudioTween::default());
+    }
+}
+
+fn play_hit_sounds(
+    mut rain_hit: EventReader<RainHit>,
+    player_query: Query<(), With<Player>>,
+    shield_query: Query<(), With<Shield>>,
+    audio: Res<Audio>,
+    audio_assets: Res<AudioAssets>,
+    volumes: Res<Volumes>,
+) {
+    let mut player_hit = false;
+    let mut shield_hit = false;
+    for RainHit(entity, kind) in rain_hit.read() {
+        // Water is harmless, so it shouldn't sound like it hurts
+        if kind.damage() == 0 {
+            continue;
+        }
+        player_hit |= player_query.contains(*entity);
+        shield_hit |= shield_query.contains(*entity);
+    }
+
+    // Many drops can hit at once, but one sound per frame is plenty
+    if player_hit {
+        audio
+            .play(audio_assets.hit_player.clone())
+            .with_volume(0.7 * volumes.sfx);
+    }
+    if shield_hit {
+        audio
+            .play(audio_assets.hit_shield.clone())
+            .with_volume(0.3 * volumes.sfx);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::app_state::GameState;
+use crate::collider::Collider;
+use crate::velocity::update_position;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Keeps a `ColliderGrid` of all colliders up to date for the simulation.
+pub struct BroadphasePlugin;
+
+impl Plugin for BroadphasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ColliderGrid>().add_systems(
+            FixedUpdate,
+            rebuild_collider_grid
+                .after(update_position)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// A spatial hash of the entities with a `Collider`, so that collision checks
+/// only need to look at the colliders near a given area. It is rebuilt from
+/// where the simulation has moved everything to at the end of each tick.
+#[derive(Resource, Default)]
+pub struct ColliderGrid {
+    /// Entities overlapping each cell, along with their collider rects.
+    cells: HashMap<IVec2, Vec<(Entity, Rect)>>,
+}
+
+impl ColliderGrid {
+    const CELL_SIZE: f32 = 64.;
+
+    /// Empties the grid, keeping the cells that get refilled by the next
+    /// inserts until `remove_empty_cells` is called.
+    pub fn clear(&mut self) {
+        for entries in self.cells.values_mut() {
+            entries.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, rect: Rect) {
+        let (min, max) = Self::cell_range(rect);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.cells
+                    .entry(IVec2::new(x, y))
+                    .or_default()
+                    .push((entity, rect));
+            }
+        }
+    }
+
+    /// Drops the cells that nothing was inserted into since the last `clear`,
+    /// so that the grid doesn't keep every cell anything ever passed through.
+    pub fn remove_empty_cells(&mut self) {
+        self.cells.retain(|_, entries| !entries.is_empty());
+    }
+
+    /// Entities whose cells overlap `rect`, each returned once along with its
+    /// collider rect. They aren't necessarily overlapping `rect` themselves.
+    pub fn query(&self, rect: Rect) -> impl Iterator<Item = (Entity, Rect)> + '_ {
+        let (min, max) = Self::cell_range(rect);
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| Some((cell, self.cells.get(&cell)?)))
+            .flat_map(move |(cell, entries)| {
+                entries
+                    .iter()
+                    // Only the first cell shared with `rect` returns the entity
+                    .filter(move |(_, entity_rect)| {
+                        cell == Self::cell_range(*entity_rect).0.max(min)
+                    })
+                    .copied()
+            })
+    }
+
+    fn cell_range(rect: Rect) -> (IVec2, IVec2) {
+        (
+            (rect.min / Self::CELL_SIZE).floor().as_ivec2(),
+            (rect.max / Self::CELL_SIZE).floor().as_ivec2(),
+        )
+    }
+}
+
+/// Rebuilds the grid once everything has moved this tick. Positions come from
+/// the `Transform`s the simulation moves rather than `GlobalTransform`s, which
+/// are only propagated once a frame and hold the interpolated rendered
+/// positions.
+pub fn rebuild_collider_grid(
+    mut grid: ResMut<ColliderGrid>,
+    collider_query: Query<(Entity, &Collider)>,
+    transform_query: Query<(&Transform, Option<&Parent>)>,
+) {
+    grid.clear();
+    for (entity, collider) in collider_query.iter() {
+        let Some(translation) = simulated_translation(entity, &transform_query) else {
+            continue;
+        };
+        grid.insert(entity, collider.rect(&translation));
+    }
+    grid.remove_empty_cells();
+}
+
+/// The world translation of `entity`, made up of its own and its ancestors'
+/// `Transform`s.
+fn simulated_translation(
+    entity: Entity,
+    transform_query: &Query<(&Transform, Option<&Parent>)>,
+) -> Option<Vec3> {
+    let (transform, mut parent) = transform_query.get(entity).ok()?;
+    let mut transform = *transform;
+    while let Some(parent_entity) = parent {
+        let (parent_transform, grandparent) = transform_query.get(parent_entity.get()).ok()?;
+        transform = parent_transform.mul_transform(transform);
+        parent = grandparent;
+    }
+    Some(transform.translation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entities_spanning_several_cells_are_returned_once() {
+        let mut grid = ColliderGrid::default();
+        let ground = Entity::from_raw(0);
+        let pillar = Entity::from_raw(1);
+        grid.insert(ground, Rect::new(-500., -100., 500., 0.));
+        grid.insert(pillar, Rect::new(100., 0., 120., 300.));
+
+        let mut found: Vec<Entity> = grid
+            .query(Rect::new(-200., -50., 200., 200.))
+            .map(|(entity, _)| entity)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![ground, pillar]);
+    }
+
+    #[test]
+    fn far_away_entities_are_not_returned() {
+        let mut grid = ColliderGrid::default();
+        grid.insert(Entity::from_raw(0), Rect::new(0., 0., 10., 10.));
+        assert_eq!(grid.query(Rect::new(500., 500., 510., 510.)).count(), 0);
+    }
+
+    #[test]
+    fn rebuilding_drops_emptied_cells() {
+        let mut grid = ColliderGrid::default();
+        let drop = Entity::from_raw(0);
+        grid.insert(drop, Rect::new(0., 0., 10., 10.));
+        grid.clear();
+        grid.insert(drop, Rect::new(200., 0., 210., 10.));
+        grid.remove_empty_cells();
+
+        assert_eq!(grid.cells.len(), 1);
+        assert_eq!(grid.query(Rect::new(0., 0., 10., 10.)).count(), 0);
+    }
+}
@@ -1,10 +1,19 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+use std::ops::BitOr;
 
 #[derive(Component)]
 pub struct Collider {
     pub size: Vec2,
     pub center: Vec2,
     pub solid: bool,
+    pub shape: ColliderShape,
+    /// Whether this collider only blocks things coming down onto it from above.
+    pub one_way: bool,
+    /// The layers this collider is on.
+    pub layers: CollisionLayers,
+    /// The layers this collider interacts with.
+    pub mask: CollisionLayers,
 }
 
 impl Collider {
@@ -28,6 +37,23 @@ impl Collider {
         self.solid = solid;
         self
     }
+
+    pub fn with_shape(mut self, shape: ColliderShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_layers(mut self, layers: CollisionLayers, mask: CollisionLayers) -> Self {
+        self.layers = layers;
+        self.mask = mask;
+        self
+    }
+
+    /// Whether this collider and something on `layers` that interacts with
+    /// `mask` should collide, which they only do if both sides opt in.
+    pub fn interacts_with(&self, layers: CollisionLayers, mask: CollisionLayers) -> bool {
+        self.solid && self.mask.intersects(layers) && mask.intersects(self.layers)
+    }
 }
 
 impl Default for Collider {
@@ -36,6 +62,78 @@ impl Default for Collider {
             size: Vec2::ZERO,
             center: Vec2::ZERO,
             solid: true,
+            shape: ColliderShape::Box,
+            one_way: false,
+            layers: CollisionLayers::TERRAIN,
+            mask: CollisionLayers::ALL,
+        }
+    }
+}
+
+/// The shape of a collider within the box given by its `size` and `center`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ColliderShape {
+    #[default]
+    Box,
+    /// A ramp filling the bottom right half of the box, rising to the right.
+    SlopeUpRight,
+    /// A ramp filling the bottom left half of the box, rising to the left.
+    SlopeUpLeft,
+    /// A circle as wide as the shortest side of the box.
+    Circle,
+}
+
+impl ColliderShape {
+    pub fn is_slope(self) -> bool {
+        matches!(self, Self::SlopeUpRight | Self::SlopeUpLeft)
+    }
+
+    /// The height of the top of the shape at `x`, when it fills `rect`.
+    /// Circles are as high as their box everywhere.
+    pub fn surface_y(self, rect: &Rect, x: f32) -> f32 {
+        let along = ((x - rect.min.x) / rect.width()).clamp(0., 1.);
+        match self {
+            Self::SlopeUpRight => rect.min.y + rect.height() * along,
+            Self::SlopeUpLeft => rect.max.y - rect.height() * along,
+            Self::Box | Self::Circle => rect.max.y,
+        }
+    }
+
+    /// The normal of the top of the shape, when it fills `rect`.
+    pub fn surface_normal(self, rect: &Rect) -> Vec2 {
+        match self {
+            Self::SlopeUpRight => Vec2::new(-rect.height(), rect.width()).normalize(),
+            Self::SlopeUpLeft => Vec2::new(rect.height(), rect.width()).normalize(),
+            Self::Box | Self::Circle => Vec2::Y,
         }
     }
 }
+
+/// A set of layers that colliders can be on or interact with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollisionLayers(u32);
+
+impl CollisionLayers {
+    pub const ALL: Self = Self(u32::MAX);
+    pub const TERRAIN: Self = Self(1 << 0);
+    pub const PLAYER: Self = Self(1 << 1);
+    pub const SHIELD: Self = Self(1 << 2);
+    pub const RAIN: Self = Self(1 << 3);
+    pub const HAZARDS: Self = Self(1 << 4);
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for CollisionLayers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::app_state::GameState;
+use crate::level::Level;
+use crate::rain::*;
+use bevy::prelude::*;
+
+/// Lets rain eat away at the level blocks that have an `Integrity`.
+pub struct CorrosionPlugin;
+
+impl Plugin for CorrosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            corrode_level
+                .after(splash_rain)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Update, show_corrosion.run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// How much more damage from rain a level block can take before it crumbles.
+#[derive(Component)]
+pub struct Integrity {
+    remaining: f32,
+    max: f32,
+}
+
+impl Integrity {
+    pub fn new(damage: f32) -> Self {
+        Self {
+            remaining: damage,
+            max: damage,
+        }
+    }
+
+    /// How much of the block has been eaten away, from 0 to 1.
+    fn corroded(&self) -> f32 {
+        1. - (self.remaining / self.max).clamp(0., 1.)
+    }
+}
+
+fn corrode_level(
+    mut commands: Commands,
+    mut rain_hit: EventReader<RainHit>,
+    mut level_query: Query<&mut Integrity, With<Level>>,
+) {
+    for RainHit(entity, kind) in rain_hit.read() {
+        let Ok(mut integrity) = level_query.get_mut(*entity) else {
+            continue;
+        };
+        if integrity.remaining <= 0. {
+            continue;
+        }
+
+        integrity.remaining -= kind.damage() as f32;
+        if integrity.remaining <= 0. {
+            commands.entity(*entity).despawn_recursive();
+        }
+    }
+}
+
+fn show_corrosion(
+    mut level_query: Query<
+        (
+            &Level,
+            &Integrity,
+            Option<&mut Sprite>,
+            Option<&Handle<ColorMaterial>>,
+        ),
+        Changed<Integrity>,
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (level, integrity, sprite, material) in level_query.iter_mut() {
+        let color = level.color(integrity.corroded());
+        if let Some(mut sprite) = sprite {
+            sprite.color = color;
+        }
+        if let Some(material) = material.and_then(|material| materials.get_mut(material)) {
+            material.color = color;
+        }
+    }
+}
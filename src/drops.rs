@@ -0,0 +1,128 @@
+use crate::collider::CollisionLayers;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::ops::RangeInclusive;
+
+/// The different kinds of raindrops, which look and hit differently.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DropKind {
+    Acid,
+    /// Deals more damage and splashes wider.
+    Heavy,
+    /// Falls faster, and goes straight through the shield.
+    Needle,
+    /// Harmless, and recharges power when it hits the player.
+    Water,
+}
+
+impl DropKind {
+    pub fn color(self) -> Color {
+        match self {
+            Self::Acid => Color::rgb(0.3, 1., 0.7),
+            Self::Heavy => Color::rgb(0.7, 1., 0.2),
+            Self::Needle => Color::rgb(0.9, 1., 0.9),
+            Self::Water => Color::rgb(0.4, 0.6, 1.),
+        }
+    }
+
+    pub fn length(self) -> RangeInclusive<f32> {
+        match self {
+            Self::Acid | Self::Water => 4.0..=16.0,
+            Self::Heavy => 6.0..=12.0,
+            Self::Needle => 20.0..=28.0,
+        }
+    }
+
+    pub fn thickness(self) -> f32 {
+        match self {
+            Self::Heavy => 3.,
+            Self::Acid | Self::Needle | Self::Water => 1.,
+        }
+    }
+
+    /// How much faster than the rain's usual speed the drop falls.
+    pub fn speed(self) -> f32 {
+        match self {
+            Self::Needle => 1.4,
+            Self::Acid | Self::Heavy | Self::Water => 1.,
+        }
+    }
+
+    /// How much faster than usual the drop's splashes fly.
+    pub fn splash(self) -> f32 {
+        match self {
+            Self::Heavy => 1.6,
+            Self::Acid | Self::Needle | Self::Water => 1.,
+        }
+    }
+
+    /// How much health the drop takes from the player.
+    pub fn damage(self) -> u8 {
+        match self {
+            Self::Acid | Self::Needle => 1,
+            Self::Heavy => 3,
+            Self::Water => 0,
+        }
+    }
+
+    /// How much power the drop gives the player.
+    pub fn power(self) -> f32 {
+        match self {
+            Self::Water => 0.02,
+            Self::Acid | Self::Heavy | Self::Needle => 0.,
+        }
+    }
+
+    /// The layers of the colliders the drop hits.
+    pub fn mask(self) -> CollisionLayers {
+        match self {
+            Self::Needle => CollisionLayers::TERRAIN | CollisionLayers::PLAYER,
+            Self::Acid | Self::Heavy | Self::Water => {
+                CollisionLayers::TERRAIN | CollisionLayers::PLAYER | CollisionLayers::SHIELD
+            }
+        }
+    }
+}
+
+/// The share of each kind of drop in the rain, with the rest being acid.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct DropMix {
+    pub heavy: f32,
+    pub needle: f32,
+    pub water: f32,
+}
+
+impl DropMix {
+    /// Picks a kind of drop with a `roll` from 0 to 1.
+    pub fn pick(&self, roll: f32) -> DropKind {
+        let mut roll = roll;
+        for (share, kind) in [
+            (self.heavy, DropKind::Heavy),
+            (self.needle, DropKind::Needle),
+            (self.water, DropKind::Water),
+        ] {
+            if roll < share {
+                return kind;
+            }
+            roll -= share;
+        }
+        DropKind::Acid
+    }
+
+    pub fn scaled(&self, heavy: f32, needle: f32, water: f32) -> Self {
+        Self {
+            heavy: self.heavy * heavy,
+            needle: self.needle * needle,
+            water: self.water * water,
+        }
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            heavy: self.heavy + (other.heavy - self.heavy) * t,
+            needle: self.needle + (other.needle - self.needle) * t,
+            water: self.water + (other.water - self.water) * t,
+        }
+    }
+}
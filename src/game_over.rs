@@ -1,21 +1,40 @@
 use bevy::prelude::*;
 
+use crate::levels::{CurrentLevel, LevelLayout};
+use crate::loading::LevelAssets;
+use crate::rain::RainRng;
+use crate::score::*;
 use crate::{app_state::*, ui::*};
 
 pub struct GameOverPlugin;
 
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
-            .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
-            .add_systems(Update, start_over.run_if(in_state(GameState::GameOver)));
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            spawn_game_over_screen.after(record_high_score),
+        )
+        .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+        .add_systems(Update, start_over.run_if(in_state(GameState::GameOver)));
     }
 }
 
 #[derive(Component)]
 pub struct GameOverScreen;
 
-fn spawn_game_over_screen(mut commands: Commands) {
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<Assets<LevelLayout>>,
+    rain_rng: Res<RainRng>,
+) {
+    let table = current_level
+        .layout(&level_assets, &layouts)
+        .map_or(&[][..], |layout| high_scores.table(&layout.name));
+
     commands
         .spawn((
             NodeBundle {
@@ -25,6 +44,7 @@ fn spawn_game_over_screen(mut commands: Commands) {
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
                     ..default()
                 },
                 background_color: Color::rgba(0., 0., 0., 0.).into(),
@@ -34,6 +54,13 @@ fn spawn_game_over_screen(mut commands: Commands) {
             GameOverScreen,
         ))
         .with_children(|children| {
+            spawn_score_summary(children, &score, table);
+            children.spawn((
+                MenuButtonLabelBundle::from_text(&format!("Seed {}", rain_rng.seed()))
+                    .with_small_font()
+                    .with_alpha(0.),
+                FadeIn::from_seconds(1.).with_background_alpha(0.),
+            ));
             children
                 .spawn((
                     MenuButtonBundle::default().with_width(Val::Px(200.0)),
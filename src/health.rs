@@ -5,10 +5,13 @@ pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Health(100))
+        app.insert_resource(Health(Health::MAX))
             .add_systems(OnEnter(AppState::InGame), spawn_health_display)
             .add_systems(OnExit(AppState::InGame), despawn_health_display)
-            .add_systems(OnExit(GameState::Playing), reset_health)
+            .add_systems(OnExit(AppState::InGame), reset_health)
+            .add_systems(OnExit(GameState::GameOver), reset_health)
+            .add_systems(OnExit(GameState::LevelComplete), reset_health)
+            .add_systems(OnExit(GameState::Restarting), reset_health)
             .add_systems(
                 Update,
                 update_health_display.run_if(in_state(GameState::Playing)),
@@ -19,6 +22,10 @@ impl Plugin for HealthPlugin {
 #[derive(Resource)]
 pub struct Health(pub u8);
 
+impl Health {
+    pub const MAX: u8 = 100;
+}
+
 #[derive(Component)]
 struct HealthDisplay;
 
@@ -66,7 +73,7 @@ fn despawn_health_display(mut commands: Commands, query: Query<Entity, With<Heal
 }
 
 fn reset_health(mut health: ResMut<Health>) {
-    health.0 = 100;
+    health.0 = Health::MAX;
 }
 
 fn update_health_display(
@@ -1,9 +1,37 @@
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::sprite::MaterialMesh2dBundle;
 
-use crate::collider::Collider;
+use crate::collider::*;
+use crate::color::lerp_colors;
 
-#[derive(Component)]
-pub struct Level;
+#[derive(Component, Default)]
+pub struct Level {
+    /// Whether the block is drawn with a texture, which is tinted rather than
+    /// painted over by its color.
+    textured: bool,
+}
+
+impl Level {
+    const COLOR: Color = Color::BLACK;
+    /// The color of a block that's about to be eaten away by the rain.
+    const COLOR_CORRODED: Color = Color::rgb(0.16, 0.3, 0.22);
+    /// Tints that make a textured block about as bright as the flat colors.
+    const TINT: Color = Color::rgb(0.12, 0.12, 0.13);
+    const TINT_CORRODED: Color = Color::rgb(0.2, 0.38, 0.28);
+    /// Size of the block texture in pixels, which meshes need to know to tile
+    /// it the same way sprites do.
+    const TEXTURE_SIZE: f32 = 32.;
+
+    /// The color of the block when the given share of it has been corroded.
+    pub fn color(&self, corroded: f32) -> Color {
+        if self.textured {
+            lerp_colors(Self::TINT, Self::TINT_CORRODED, corroded)
+        } else {
+            lerp_colors(Self::COLOR, Self::COLOR_CORRODED, corroded)
+        }
+    }
+}
 
 #[derive(Bundle)]
 pub struct LevelBundle {
@@ -17,19 +45,148 @@ impl LevelBundle {
         Self {
             sprite: SpriteBundle {
                 sprite: Sprite {
-                    color: Color::BLACK,
+                    color: Level::COLOR,
                     custom_size: Some(size),
                     ..default()
                 },
                 transform: Transform::from_translation(position.extend(0.)),
                 ..default()
             },
-            collider: Collider::from_size(size),
-            level: Level,
+            collider: level_collider(size),
+            level: Level::default(),
         }
     }
 
     pub fn from_min_max(min: Vec2, max: Vec2) -> Self {
         Self::from_center_size((min + max) / 2., max - min)
     }
+
+    pub fn with_one_way(mut self, one_way: bool) -> Self {
+        self.collider.one_way = one_way;
+        self
+    }
+
+    /// Tiles the texture across the block, if there is one.
+    pub fn with_texture(mut self, texture: Option<Handle<Image>>) -> Self {
+        let Some(texture) = texture else {
+            return self;
+        };
+        let size = self.sprite.sprite.custom_size.unwrap_or_default();
+        self.level.textured = true;
+        self.sprite.texture = texture;
+        self.sprite.sprite.color = self.level.color(0.);
+        // Reaches past the edges of the texture, which repeats to fill it
+        self.sprite.sprite.rect = Some(Rect::from_corners(Vec2::ZERO, size));
+        self
+    }
+}
+
+/// A level block that isn't a box, so it's drawn with a mesh instead of a
+/// sprite.
+#[derive(Bundle)]
+pub struct ShapedLevelBundle {
+    mesh: MaterialMesh2dBundle<ColorMaterial>,
+    collider: Collider,
+    level: Level,
+}
+
+impl ShapedLevelBundle {
+    pub fn from_min_max(
+        min: Vec2,
+        max: Vec2,
+        shape: ColliderShape,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+    ) -> Self {
+        let size = max - min;
+        Self {
+            mesh: MaterialMesh2dBundle {
+                mesh: meshes.add(shape_mesh(shape, size)).into(),
+                material: materials.add(ColorMaterial::from(Level::COLOR)),
+                transform: Transform::from_translation(((min + max) / 2.).extend(0.)),
+                ..default()
+            },
+            collider: level_collider(size).with_shape(shape),
+            level: Level::default(),
+        }
+    }
+
+    pub fn with_one_way(mut self, one_way: bool) -> Self {
+        self.collider.one_way = one_way;
+        self
+    }
+
+    /// Tiles the texture across the block, if there is one.
+    pub fn with_texture(
+        mut self,
+        texture: Option<Handle<Image>>,
+        materials: &mut Assets<ColorMaterial>,
+    ) -> Self {
+        let Some(texture) = texture else {
+            return self;
+        };
+        self.level.textured = true;
+        if let Some(material) = materials.get_mut(&self.mesh.material) {
+            material.color = self.level.color(0.);
+            material.texture = Some(texture);
+        }
+        self
+    }
+}
+
+fn level_collider(size: Vec2) -> Collider {
+    Collider::from_size(size).with_layers(
+        CollisionLayers::TERRAIN,
+        CollisionLayers::PLAYER | CollisionLayers::RAIN,
+    )
+}
+
+/// Builds the mesh of a block, with UVs that reach past the edges of the
+/// texture so that it tiles one pixel per unit, like it does on sprites.
+fn shape_mesh(shape: ColliderShape, size: Vec2) -> Mesh {
+    let mut mesh = untiled_shape_mesh(shape, size);
+    let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+    else {
+        return mesh;
+    };
+    for uv in uvs.iter_mut() {
+        uv[0] *= size.x / Level::TEXTURE_SIZE;
+        uv[1] *= size.y / Level::TEXTURE_SIZE;
+    }
+    mesh
+}
+
+fn untiled_shape_mesh(shape: ColliderShape, size: Vec2) -> Mesh {
+    let half = size / 2.;
+    let corners = match shape {
+        ColliderShape::Box => {
+            return shape::Quad::new(size).into();
+        }
+        ColliderShape::Circle => {
+            return shape::Circle::new(size.min_element() / 2.).into();
+        }
+        ColliderShape::SlopeUpRight => [
+            [-half.x, -half.y, 0.],
+            [half.x, -half.y, 0.],
+            [half.x, half.y, 0.],
+        ],
+        ColliderShape::SlopeUpLeft => [
+            [-half.x, -half.y, 0.],
+            [half.x, -half.y, 0.],
+            [-half.x, half.y, 0.],
+        ],
+    };
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, corners.to_vec());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0., 0., 1.]; 3]);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_UV_0,
+        corners
+            .iter()
+            .map(|[x, y, _]| [x / size.x + 0.5, 0.5 - y / size.y])
+            .collect::<Vec<_>>(),
+    );
+    mesh.set_indices(Some(Indices::U32(vec![0, 1, 2])));
+    mesh
 }
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::levels::{CurrentLevel, LevelLayout};
+use crate::loading::LevelAssets;
+use crate::score::*;
+use crate::{app_state::*, ui::*};
+
+pub struct LevelCompletePlugin;
+
+impl Plugin for LevelCompletePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::LevelComplete),
+            spawn_level_complete_screen.after(record_high_score),
+        )
+        .add_systems(
+            OnExit(GameState::LevelComplete),
+            despawn_level_complete_screen,
+        )
+        .add_systems(
+            Update,
+            click_button.run_if(in_state(GameState::LevelComplete)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct LevelCompleteScreen;
+
+fn spawn_level_complete_screen(
+    mut commands: Commands,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<Assets<LevelLayout>>,
+) {
+    let table = current_level
+        .layout(&level_assets, &layouts)
+        .map_or(&[][..], |layout| high_scores.table(&layout.name));
+    let (label, action) = if current_level.has_next(&level_assets) {
+        ("Next level", MenuAction::NextLevel)
+    } else {
+        ("Back to menu", MenuAction::BackToMenu)
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.).into(),
+                ..default()
+            },
+            FadeIn::from_seconds(0.5).with_background_alpha(0.8),
+            LevelCompleteScreen,
+        ))
+        .with_children(|children| {
+            children.spawn((
+                MenuButtonLabelBundle::from_text("Level complete").with_alpha(0.),
+                FadeIn::from_seconds(1.).with_background_alpha(0.),
+            ));
+            spawn_score_summary(children, &score, table);
+            children
+                .spawn((
+                    MenuButtonBundle::default().with_width(Val::Px(250.0)),
+                    action,
+                    FadeIn::from_seconds(1.),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        MenuButtonLabelBundle::from_text(label).with_alpha(0.),
+                        FadeIn::from_seconds(1.).with_background_alpha(0.),
+                    ));
+                });
+        });
+}
+
+fn despawn_level_complete_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<LevelCompleteScreen>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+enum MenuAction {
+    NextLevel,
+    BackToMenu,
+}
+
+fn click_button(
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::NextLevel => {
+                current_level.0 += 1;
+                next_game_state.set(GameState::Playing);
+            }
+            MenuAction::BackToMenu => {
+                next_app_state.set(AppState::Menu);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::collider::ColliderShape;
+use crate::rain::RainSettings;
+use crate::weather::WeatherPhase;
+use crate::wind::WindSettings;
+
+/// A level as described by a `.level.ron` file in `assets/levels`.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct LevelLayout {
+    pub name: String,
+    /// How long the player needs to survive to complete the level.
+    pub survive_seconds: f32,
+    pub player_spawn: Vec2,
+    #[serde(default)]
+    pub rain: RainSettings,
+    /// Phases of weather that change the rain, repeating once they have all passed.
+    #[serde(default)]
+    pub weather: Vec<WeatherPhase>,
+    #[serde(default)]
+    pub wind: WindSettings,
+    pub blocks: Vec<BlockLayout>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BlockLayout {
+    pub min: Vec2,
+    pub max: Vec2,
+    #[serde(default)]
+    pub shape: ColliderShape,
+    /// Whether the block can be jumped up through from below.
+    #[serde(default)]
+    pub one_way: bool,
+    /// How many rain hits the block can take before it crumbles. Blocks
+    /// without one never do.
+    #[serde(default)]
+    pub integrity: Option<f32>,
+}
+
+#[derive(Default)]
+pub struct LevelLayoutLoader;
+
+#[derive(Debug, Error)]
+pub enum LevelLayoutLoaderError {
+    #[error("Could not read level file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse level file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for LevelLayoutLoader {
+    type Asset = LevelLayout;
+    type Settings = ();
+    type Error = LevelLayoutLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes::<LevelLayout>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
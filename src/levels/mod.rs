@@ -1,11 +1,168 @@
-mod level1;
+mod layout;
 
+use crate::{
+    app_state::*,
+    collider::ColliderShape,
+    corrosion::Integrity,
+    level::*,
+    loading::{LevelAssets, Textures},
+    persistence::*,
+    player::spawn_player,
+    rain::*,
+    weather::WeatherDirector,
+    wind::Wind,
+};
 use bevy::prelude::*;
+pub use layout::*;
+use serde::{Deserialize, Serialize};
 
 pub struct LevelsPlugin;
 
 impl Plugin for LevelsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(level1::Level1Plugin);
+        app.init_resource::<CurrentLevel>()
+            .init_persistent_resource::<LevelProgress>()
+            .insert_resource(LevelTimer(Timer::default()))
+            .init_resource::<LevelSpawned>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_level.run_if(resource_equals(LevelSpawned(false))),
+            )
+            .add_systems(OnEnter(GameState::LevelComplete), unlock_next_level)
+            .add_systems(OnExit(GameState::GameOver), despawn_level)
+            .add_systems(OnExit(GameState::LevelComplete), despawn_level)
+            .add_systems(OnExit(GameState::Restarting), despawn_level)
+            .add_systems(OnExit(AppState::InGame), despawn_level)
+            .add_systems(Update, complete_level.run_if(in_state(GameState::Playing)));
     }
 }
+
+/// Index into `LevelAssets::layouts` of the level that gets spawned when
+/// entering `GameState::Playing` without a level already in place.
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub usize);
+
+impl CurrentLevel {
+    pub fn has_next(&self, level_assets: &LevelAssets) -> bool {
+        self.0 + 1 < level_assets.layouts.len()
+    }
+
+    pub fn layout<'a>(
+        &self,
+        level_assets: &LevelAssets,
+        layouts: &'a Assets<LevelLayout>,
+    ) -> Option<&'a LevelLayout> {
+        layouts.get(level_assets.layouts.get(self.0)?)
+    }
+}
+
+/// Which levels the player has unlocked so far.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct LevelProgress {
+    /// Index of the furthest level that can be played.
+    pub unlocked: usize,
+}
+
+impl Persistent for LevelProgress {
+    const KEY: &'static str = "level_progress";
+    const VERSION: u32 = 1;
+}
+
+impl LevelProgress {
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level <= self.unlocked
+    }
+}
+
+/// Whether the current level has been spawned, so that resuming from the
+/// pause menu doesn't spawn it again. Blocks can crumble away, so whether any
+/// are left doesn't tell.
+#[derive(Resource, Default, PartialEq)]
+struct LevelSpawned(bool);
+
+/// Counts down the time left to survive in the current level.
+#[derive(Resource)]
+pub struct LevelTimer(pub Timer);
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_level(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<Assets<LevelLayout>>,
+    mut level_timer: ResMut<LevelTimer>,
+    seed_override: Res<SeedOverride>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    textures: Textures,
+    mut level_spawned: ResMut<LevelSpawned>,
+) {
+    let Some(layout) = current_level.layout(&level_assets, &layouts) else {
+        error!("No level layout available for level {}", current_level.0);
+        return;
+    };
+    level_spawned.0 = true;
+
+    info!("Spawning level \"{}\"", layout.name);
+    for block in layout.blocks.iter() {
+        let mut block_commands = match block.shape {
+            ColliderShape::Box => commands.spawn(
+                LevelBundle::from_min_max(block.min, block.max)
+                    .with_one_way(block.one_way)
+                    .with_texture(textures.block()),
+            ),
+            shape => commands.spawn(
+                ShapedLevelBundle::from_min_max(
+                    block.min,
+                    block.max,
+                    shape,
+                    &mut meshes,
+                    &mut materials,
+                )
+                .with_one_way(block.one_way)
+                .with_texture(textures.block(), &mut materials),
+            ),
+        };
+        if let Some(integrity) = block.integrity {
+            block_commands.insert((Integrity::new(integrity), RainHitListener));
+        }
+    }
+    commands.insert_resource(layout.rain.clone());
+    commands.insert_resource(WeatherDirector::new(
+        layout.rain.clone(),
+        layout.weather.clone(),
+    ));
+    commands.insert_resource(Wind::new(layout.wind.clone()));
+    commands.insert_resource(RainRng::for_run(&layout.rain, &seed_override));
+    level_timer.0 = Timer::from_seconds(layout.survive_seconds, TimerMode::Once);
+
+    spawn_player(commands, layout.player_spawn.extend(1.), &textures);
+}
+
+fn complete_level(
+    time: Res<Time>,
+    mut level_timer: ResMut<LevelTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if level_timer.0.tick(time.delta()).just_finished() {
+        next_state.set(GameState::LevelComplete);
+    }
+}
+
+fn unlock_next_level(current_level: Res<CurrentLevel>, mut progress: ResMut<LevelProgress>) {
+    // Only touch the progress when it actually changes, so it is not saved needlessly
+    if current_level.0 + 1 > progress.unlocked {
+        progress.unlocked = current_level.0 + 1;
+    }
+}
+
+fn despawn_level(
+    mut commands: Commands,
+    query: Query<Entity, With<Level>>,
+    mut level_spawned: ResMut<LevelSpawned>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    level_spawned.0 = false;
+}
@@ -2,34 +2,62 @@
 
 mod actions;
 mod app_state;
+mod args;
+mod audio;
+mod broadphase;
 mod collider;
 mod color;
+mod corrosion;
+mod drops;
 mod game_over;
 mod health;
 mod level;
+mod level_complete;
 mod levels;
 mod loading;
 mod menu;
+mod music;
+mod pause;
+mod persistence;
 mod player;
 mod power;
+mod puddles;
 mod rain;
+mod score;
+mod settings;
 mod shield;
+mod simulation;
+mod sweep;
 mod ui;
 mod velocity;
+mod weather;
+mod wind;
 
 use crate::actions::ActionsPlugin;
 use crate::app_state::AppStatePlugin;
+use crate::audio::InternalAudioPlugin;
+use crate::broadphase::BroadphasePlugin;
+use crate::corrosion::CorrosionPlugin;
 use crate::game_over::GameOverPlugin;
 use crate::health::HealthPlugin;
+use crate::level_complete::LevelCompletePlugin;
 use crate::levels::LevelsPlugin;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
+use crate::music::MusicPlugin;
+use crate::pause::PausePlugin;
 use crate::player::PlayerPlugin;
 use crate::power::PowerPlugin;
+use crate::puddles::PuddlePlugin;
 use crate::rain::RainPlugin;
+use crate::score::ScorePlugin;
+use crate::settings::SettingsPlugin;
 use crate::shield::ShieldPlugin;
+use crate::simulation::SimulationPlugin;
 use crate::ui::UiPlugin;
 use crate::velocity::VelocityPlugin;
+use crate::weather::WeatherPlugin;
+use crate::wind::WindPlugin;
 
 use bevy::app::App;
 #[cfg(debug_assertions)]
@@ -45,17 +73,31 @@ impl Plugin for GamePlugin {
         app.add_plugins((
             AppStatePlugin,
             LoadingPlugin,
+            InternalAudioPlugin,
+            MusicPlugin,
+            SettingsPlugin,
             UiPlugin,
             MenuPlugin,
             LevelsPlugin,
             ActionsPlugin,
             GameOverPlugin,
+            LevelCompletePlugin,
+            PausePlugin,
+        ))
+        .add_plugins((
             PlayerPlugin,
             ShieldPlugin,
             HealthPlugin,
             PowerPlugin,
             RainPlugin,
+            ScorePlugin,
+            BroadphasePlugin,
+            CorrosionPlugin,
+            PuddlePlugin,
+            SimulationPlugin,
             VelocityPlugin,
+            WeatherPlugin,
+            WindPlugin,
         ));
 
         #[cfg(debug_assertions)]
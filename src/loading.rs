@@ -1,16 +1,31 @@
 use crate::app_state::AppState;
+use crate::levels::{LevelLayout, LevelLayoutLoader};
+use crate::music::{MusicTrack, MusicTrackLoader};
+use bevy::asset::LoadState;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::render::texture::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
 use bevy_asset_loader::prelude::*;
+use bevy_kira_audio::AudioSource;
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(AppState::Loading).continue_to_state(AppState::Menu),
-        )
-        .add_collection_to_loading_state::<_, AudioAssets>(AppState::Loading)
-        .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading);
+        app.init_asset::<LevelLayout>()
+            .init_asset_loader::<LevelLayoutLoader>()
+            .init_asset::<MusicTrack>()
+            .init_asset_loader::<MusicTrackLoader>()
+            .add_loading_state(
+                LoadingState::new(AppState::Loading).continue_to_state(AppState::Menu),
+            )
+            .add_collection_to_loading_state::<_, AudioAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, LevelAssets>(AppState::Loading)
+            // Not part of the loading state, so that failing to load them falls
+            // back to flat colors rather than getting stuck loading
+            .init_collection::<TextureAssets>()
+            .add_systems(Startup, add_blank_atlas)
+            .add_systems(Update, repeat_block_texture);
     }
 }
 
@@ -18,7 +33,117 @@ impl Plugin for LoadingPlugin {
 // when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
 
 #[derive(AssetCollection, Resource)]
-pub struct AudioAssets {}
+pub struct AudioAssets {
+    #[asset(path = "audio/rain.wav")]
+    pub rain: Handle<AudioSource>,
+    #[asset(path = "audio/hit_player.wav")]
+    pub hit_player: Handle<AudioSource>,
+    #[asset(path = "audio/hit_shield.wav")]
+    pub hit_shield: Handle<AudioSource>,
+    #[asset(path = "audio/menu.wav")]
+    pub menu: Handle<AudioSource>,
+    #[asset(path = "music/danger.music.ron")]
+    pub music: Handle<MusicTrack>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct TextureAssets {
+    /// Frames of the player's animations, side by side.
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 12, rows = 1))]
+    #[asset(path = "textures/player.png")]
+    pub player: Handle<TextureAtlas>,
+    #[asset(path = "textures/shield.png")]
+    pub shield: Handle<Image>,
+    /// Tiled across level blocks, one pixel per unit.
+    #[asset(path = "textures/block.png")]
+    pub block: Handle<Image>,
+    #[asset(path = "textures/drop.png")]
+    pub drop: Handle<Image>,
+}
+
+/// Hands out the `TextureAssets`, or `None` for those that failed to load so
+/// that flat colors can be drawn in their place.
+#[derive(SystemParam)]
+pub struct Textures<'w> {
+    assets: Res<'w, TextureAssets>,
+    asset_server: Res<'w, AssetServer>,
+    atlases: Res<'w, Assets<TextureAtlas>>,
+    blank_atlas: Res<'w, BlankAtlas>,
+}
+
+impl Textures<'_> {
+    pub fn player(&self) -> Option<Handle<TextureAtlas>> {
+        let atlas = self.atlases.get(&self.assets.player)?;
+        self.get(&atlas.texture)?;
+        Some(self.assets.player.clone())
+    }
+
+    /// An atlas of a single blank frame, to draw flat colors with in place of
+    /// one that failed to load.
+    pub fn blank_atlas(&self) -> Handle<TextureAtlas> {
+        self.blank_atlas.0.clone()
+    }
+
+    pub fn shield(&self) -> Option<Handle<Image>> {
+        self.get(&self.assets.shield)
+    }
+
+    pub fn block(&self) -> Option<Handle<Image>> {
+        self.get(&self.assets.block)
+    }
+
+    pub fn drop(&self) -> Option<Handle<Image>> {
+        self.get(&self.assets.drop)
+    }
+
+    fn get(&self, handle: &Handle<Image>) -> Option<Handle<Image>> {
+        match self.asset_server.get_load_state(handle) {
+            Some(LoadState::Failed) => None,
+            _ => Some(handle.clone()),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct BlankAtlas(Handle<TextureAtlas>);
 
+fn add_blank_atlas(mut commands: Commands, mut atlases: ResMut<Assets<TextureAtlas>>) {
+    let atlas = TextureAtlas::from_grid(Handle::default(), Vec2::ONE, 1, 1, None, None);
+    commands.insert_resource(BlankAtlas(atlases.add(atlas)));
+}
+
+fn repeat_block_texture(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    texture_assets: Res<TextureAssets>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in image_events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = *event else {
+            continue;
+        };
+        if id != texture_assets.block.id() {
+            continue;
+        }
+        if let Some(image) = images.get_mut(id) {
+            image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+                address_mode_u: ImageAddressMode::Repeat,
+                address_mode_v: ImageAddressMode::Repeat,
+                ..default()
+            });
+        }
+    }
+}
+
+/// All levels of the game, in the order they are played.
 #[derive(AssetCollection, Resource)]
-pub struct TextureAssets {}
+pub struct LevelAssets {
+    #[asset(
+        paths(
+            "levels/level1.level.ron",
+            "levels/level2.level.ron",
+            "levels/level3.level.ron"
+        ),
+        collection(typed)
+    )]
+    pub layouts: Vec<Handle<LevelLayout>>,
+}
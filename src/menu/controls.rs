@@ -0,0 +1,240 @@
+use crate::{actions::*, app_state::*, ui::*};
+use bevy::prelude::*;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(MenuState::Controls), setup_controls)
+            .add_systems(OnExit(MenuState::Controls), despawn_controls)
+            .add_systems(
+                Update,
+                (click_button, capture_binding, update_slot_labels)
+                    .chain()
+                    .run_if(in_state(MenuState::Controls)),
+            );
+    }
+}
+
+/// How many bindings are shown, and can be set, per control.
+const SLOTS: usize = 3;
+
+#[derive(Component)]
+struct Controls;
+
+#[derive(Component)]
+struct ControlsStatus;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+struct BindingSlot {
+    control: GameControl,
+    index: usize,
+}
+
+/// The slot waiting for a key or button to be pressed, if any.
+#[derive(Resource, Default)]
+struct Rebinding(Option<BindingSlot>);
+
+fn setup_controls(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                ..default()
+            },
+            Controls,
+        ))
+        .with_children(|children| {
+            for control in GameControl::ALL {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(150.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(MenuButtonLabelBundle::from_text(&format!("{control:?}")));
+                        });
+                        for index in 0..SLOTS {
+                            let slot = BindingSlot { control, index };
+                            row.spawn((
+                                MenuButtonBundle::default().with_width(Val::Px(200.0)),
+                                MenuAction::Rebind(slot),
+                                slot,
+                            ))
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(MenuButtonLabelBundle::from_text("").with_small_font());
+                            });
+                        }
+                    });
+            }
+
+            children.spawn((
+                MenuButtonLabelBundle::from_text("Click a slot to rebind it").with_small_font(),
+                ControlsStatus,
+            ));
+
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(10.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        MenuButtonBundle::transparent().with_width(Val::Px(320.0)),
+                        MenuAction::ResetDefaults,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(MenuButtonLabelBundle::from_text("Reset to defaults"));
+                    });
+                    row.spawn((MenuButtonBundle::transparent(), MenuAction::Back))
+                        .with_children(|parent| {
+                            parent.spawn(MenuButtonLabelBundle::from_text("Back"));
+                        });
+                });
+        });
+}
+
+#[derive(Component)]
+enum MenuAction {
+    Rebind(BindingSlot),
+    ResetDefaults,
+    Back,
+}
+
+fn click_button(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    mut next_state: ResMut<NextState<MenuState>>,
+    mut status_query: Query<&mut Text, With<ControlsStatus>>,
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::Rebind(slot) => {
+                if rebinding.0 == Some(*slot) {
+                    rebinding.0 = None;
+                    set_status(&mut status_query, "Click a slot to rebind it");
+                } else {
+                    rebinding.0 = Some(*slot);
+                    set_status(
+                        &mut status_query,
+                        "Press a key or button, Backspace to clear the slot",
+                    );
+                }
+            }
+            MenuAction::ResetDefaults => {
+                *bindings = InputBindings::default();
+                rebinding.0 = None;
+                set_status(&mut status_query, "Controls reset to defaults");
+            }
+            MenuAction::Back => {
+                rebinding.0 = None;
+                next_state.set(MenuState::Main);
+            }
+        }
+    }
+}
+
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: GamepadInput,
+    mut status_query: Query<&mut Text, With<ControlsStatus>>,
+) {
+    let Some(slot) = rebinding.0 else {
+        return;
+    };
+
+    let binding = if let Some(key) = keyboard_input.get_just_pressed().next() {
+        Binding::Key(*key)
+    } else if let Some(button) = gamepad_input.first_just_pressed() {
+        Binding::GamepadButton(button)
+    } else {
+        return;
+    };
+    rebinding.0 = None;
+
+    if binding == Binding::Key(KeyCode::Back) {
+        bindings.unbind(slot.control, slot.index);
+        set_status(&mut status_query, "Click a slot to rebind it");
+        return;
+    }
+
+    match bindings.bind(slot.control, slot.index, binding) {
+        Some(conflict) => set_status(
+            &mut status_query,
+            &format!(
+                "{binding} was moved from {conflict:?} to {:?}",
+                slot.control
+            ),
+        ),
+        None => set_status(&mut status_query, "Click a slot to rebind it"),
+    }
+}
+
+fn set_status(status_query: &mut Query<&mut Text, With<ControlsStatus>>, status: &str) {
+    for mut text in status_query.iter_mut() {
+        text.sections[0].value = status.to_string();
+    }
+}
+
+fn update_slot_labels(
+    rebinding: Res<Rebinding>,
+    bindings: Res<InputBindings>,
+    slot_query: Query<(&BindingSlot, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (slot, children) in slot_query.iter() {
+        let label = if rebinding.0 == Some(*slot) {
+            "...".to_string()
+        } else {
+            bindings
+                .get(slot.control)
+                .get(slot.index)
+                .map_or("-".to_string(), Binding::to_string)
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+fn despawn_controls(mut commands: Commands, query: Query<Entity, With<Controls>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
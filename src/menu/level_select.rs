@@ -0,0 +1,120 @@
+use crate::{app_state::*, levels::*, loading::LevelAssets, score::HighScores, ui::*};
+use bevy::prelude::*;
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::LevelSelect), setup_level_select)
+            .add_systems(OnExit(MenuState::LevelSelect), despawn_level_select)
+            .add_systems(
+                Update,
+                click_button.run_if(in_state(MenuState::LevelSelect)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct LevelSelect;
+
+fn setup_level_select(
+    mut commands: Commands,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<Assets<LevelLayout>>,
+    progress: Res<LevelProgress>,
+    high_scores: Res<HighScores>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                ..default()
+            },
+            LevelSelect,
+        ))
+        .with_children(|children| {
+            for (index, handle) in level_assets.layouts.iter().enumerate() {
+                let name = layouts
+                    .get(handle)
+                    .map_or("Unknown level", |layout| layout.name.as_str());
+
+                if progress.is_unlocked(index) {
+                    children
+                        .spawn((
+                            MenuButtonBundle::default().with_width(Val::Px(400.0)),
+                            MenuAction::Play(index),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(MenuButtonLabelBundle::from_text(&format!(
+                                "{}. {name}",
+                                index + 1
+                            )));
+                            if let Some(best) = high_scores.best(name) {
+                                parent.spawn(
+                                    MenuButtonLabelBundle::from_text(&format!("  Best {best}"))
+                                        .with_small_font(),
+                                );
+                            }
+                        });
+                } else {
+                    children
+                        .spawn(MenuButtonBundle::disabled().with_width(Val::Px(400.0)))
+                        .with_children(|parent| {
+                            parent.spawn(
+                                MenuButtonLabelBundle::from_text(&format!("{}. Locked", index + 1))
+                                    .with_alpha(0.4),
+                            );
+                        });
+                }
+            }
+
+            children
+                .spawn((MenuButtonBundle::transparent(), MenuAction::Back))
+                .with_children(|parent| {
+                    parent.spawn(MenuButtonLabelBundle::from_text("Back"));
+                });
+        });
+}
+
+#[derive(Component)]
+enum MenuAction {
+    Play(usize),
+    Back,
+}
+
+fn click_button(
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::Play(level) => {
+                current_level.0 = *level;
+                next_app_state.set(AppState::InGame);
+            }
+            MenuAction::Back => {
+                next_menu_state.set(MenuState::Main);
+            }
+        }
+    }
+}
+
+fn despawn_level_select(mut commands: Commands, query: Query<Entity, With<LevelSelect>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,3 +1,7 @@
+mod controls;
+mod level_select;
+mod sound;
+
 use crate::{app_state::*, ui::*};
 use bevy::prelude::*;
 
@@ -5,17 +9,26 @@ pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Menu), setup_menu)
-            .add_systems(OnExit(AppState::Menu), despawn_menu)
-            .add_systems(Update, click_button.run_if(in_state(AppState::Menu)));
+        app.add_plugins((
+            level_select::LevelSelectPlugin,
+            controls::ControlsPlugin,
+            sound::SoundPlugin,
+        ))
+        .add_systems(Startup, spawn_camera)
+        .add_systems(OnEnter(MenuState::Main), setup_menu)
+        .add_systems(OnExit(MenuState::Main), despawn_menu)
+        .add_systems(Update, click_button.run_if(in_state(MenuState::Main)));
     }
 }
 
 #[derive(Component)]
 struct Menu;
 
-fn setup_menu(mut commands: Commands) {
+fn spawn_camera(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
+}
+
+fn setup_menu(mut commands: Commands) {
     commands
         .spawn((
             NodeBundle {
@@ -25,6 +38,7 @@ fn setup_menu(mut commands: Commands) {
                     flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
                     ..default()
                 },
                 ..default()
@@ -34,12 +48,28 @@ fn setup_menu(mut commands: Commands) {
         .with_children(|children| {
             children
                 .spawn((
-                    MenuButtonBundle::default().with_width(Val::Px(140.0)),
-                    MenuAction::ChangeState(AppState::InGame),
+                    MenuButtonBundle::default().with_width(Val::Px(200.0)),
+                    MenuAction::ChangeState(MenuState::LevelSelect),
                 ))
                 .with_children(|parent| {
                     parent.spawn(MenuButtonLabelBundle::from_text("Play"));
                 });
+            children
+                .spawn((
+                    MenuButtonBundle::default().with_width(Val::Px(200.0)),
+                    MenuAction::ChangeState(MenuState::Controls),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(MenuButtonLabelBundle::from_text("Controls"));
+                });
+            children
+                .spawn((
+                    MenuButtonBundle::default().with_width(Val::Px(200.0)),
+                    MenuAction::ChangeState(MenuState::Sound),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(MenuButtonLabelBundle::from_text("Sound"));
+                });
         });
     commands
         .spawn((
@@ -84,12 +114,12 @@ fn setup_menu(mut commands: Commands) {
 
 #[derive(Component)]
 enum MenuAction {
-    ChangeState(AppState),
+    ChangeState(MenuState),
     OpenLink(&'static str),
 }
 
 fn click_button(
-    mut next_state: ResMut<NextState<AppState>>,
+    mut next_state: ResMut<NextState<MenuState>>,
     interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
 ) {
     for (interaction, action) in interaction_query.iter() {
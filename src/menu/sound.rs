@@ -0,0 +1,185 @@
+use crate::{app_state::*, settings::AudioSettings, ui::*};
+use bevy::prelude::*;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Sound), setup_sound)
+            .add_systems(OnExit(MenuState::Sound), despawn_sound)
+            .add_systems(
+                Update,
+                (click_button, set_volume, update_mute_label)
+                    .chain()
+                    .run_if(in_state(MenuState::Sound)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct Sound;
+
+#[derive(Component)]
+struct MuteLabel;
+
+/// Which volume of the `AudioSettings` a slider sets.
+#[derive(Component, Clone, Copy, Debug)]
+enum Volume {
+    Master,
+    Music,
+    Effects,
+}
+
+impl Volume {
+    const ALL: [Volume; 3] = [Volume::Master, Volume::Music, Volume::Effects];
+
+    fn get(self, settings: &AudioSettings) -> f32 {
+        match self {
+            Volume::Master => settings.master,
+            Volume::Music => settings.music,
+            Volume::Effects => settings.sfx,
+        }
+    }
+
+    fn set(self, settings: &mut AudioSettings, value: f32) {
+        match self {
+            Volume::Master => settings.master = value,
+            Volume::Music => settings.music = value,
+            Volume::Effects => settings.sfx = value,
+        }
+    }
+}
+
+fn setup_sound(mut commands: Commands, settings: Res<AudioSettings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                ..default()
+            },
+            Sound,
+        ))
+        .with_children(|children| {
+            for volume in Volume::ALL {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(150.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(MenuButtonLabelBundle::from_text(&format!("{volume:?}")));
+                        });
+                        row.spawn((SliderBundle::new(volume.get(&settings)), volume))
+                            .with_children(|parent| {
+                                parent.spawn(SliderFillBundle::default());
+                            });
+                    });
+            }
+
+            children
+                .spawn((
+                    MenuButtonBundle::default().with_width(Val::Px(460.0)),
+                    MenuAction::ToggleMute,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((MenuButtonLabelBundle::from_text(""), MuteLabel));
+                });
+
+            children
+                .spawn((MenuButtonBundle::transparent(), MenuAction::Back))
+                .with_children(|parent| {
+                    parent.spawn(MenuButtonLabelBundle::from_text("Back"));
+                });
+        });
+}
+
+#[derive(Component)]
+enum MenuAction {
+    ToggleMute,
+    Back,
+}
+
+fn click_button(
+    mut settings: ResMut<AudioSettings>,
+    mut next_state: ResMut<NextState<MenuState>>,
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::ToggleMute => {
+                settings.mute_on_focus_loss = !settings.mute_on_focus_loss;
+            }
+            MenuAction::Back => {
+                next_state.set(MenuState::Main);
+            }
+        }
+    }
+}
+
+/// Applies slider values to the volumes as they are dragged, but only marks
+/// the settings as changed, which saves them, once a slider is let go of.
+fn set_volume(
+    mut settings: ResMut<AudioSettings>,
+    mut unsaved: Local<bool>,
+    slider_query: Query<
+        (&Slider, &Volume, &Interaction),
+        Or<(Changed<Slider>, Changed<Interaction>)>,
+    >,
+) {
+    for (slider, volume, interaction) in slider_query.iter() {
+        // Sliders are also changed when spawned, which shouldn't save anything
+        if volume.get(&settings) != slider.value {
+            volume.set(settings.bypass_change_detection(), slider.value);
+            *unsaved = true;
+        }
+        if *interaction != Interaction::Pressed && *unsaved {
+            settings.set_changed();
+            *unsaved = false;
+        }
+    }
+}
+
+fn update_mute_label(
+    settings: Res<AudioSettings>,
+    mut label_query: Query<&mut Text, With<MuteLabel>>,
+) {
+    let label = if settings.mute_on_focus_loss {
+        "Mute in background: On"
+    } else {
+        "Mute in background: Off"
+    };
+    for mut text in label_query.iter_mut() {
+        text.sections[0].value = label.to_string();
+    }
+}
+
+fn despawn_sound(mut commands: Commands, query: Query<Entity, With<Sound>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
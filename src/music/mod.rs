@@ -0,0 +1,190 @@
+mod track;
+
+use crate::app_state::*;
+use crate::audio::{RainIntensity, Volumes};
+use crate::health::Health;
+use crate::loading::AudioAssets;
+use crate::power::Power;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use std::time::Duration;
+pub use track::*;
+
+/// Plays the `MusicTrack` of the game while playing, fading its stems in and
+/// out with the danger the player is in.
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicStems>()
+            .add_systems(OnEnter(GameState::GameOver), (stop_stems, play_sting))
+            .add_systems(OnExit(GameState::GameOver), stop_sting)
+            .add_systems(OnExit(AppState::InGame), stop_stems)
+            .add_systems(OnEnter(GameState::Paused), pause_stems)
+            .add_systems(OnExit(GameState::Paused), resume_stems)
+            .add_systems(
+                Update,
+                (
+                    start_stems.run_if(in_state(GameState::Playing)),
+                    follow_danger.run_if(in_state(AppState::InGame)),
+                    follow_sting_volume.run_if(resource_changed::<Volumes>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The volumes of the stems of the `MusicTrack` while they are playing, each
+/// on its own dynamic channel. The channels are kept around once created, so
+/// that stopping them can fade out.
+#[derive(Resource, Default)]
+struct MusicStems {
+    volumes: Option<Vec<f64>>,
+}
+
+impl MusicStems {
+    /// How quickly the stems follow changes in danger.
+    const FOLLOW_SPEED: f64 = 0.8;
+
+    fn channel(index: usize) -> String {
+        format!("music_stem_{index}")
+    }
+}
+
+/// The sting playing on the game over screen.
+#[derive(Resource)]
+struct Sting(Handle<AudioInstance>);
+
+const FADE_OUT: Duration = Duration::from_millis(500);
+
+fn start_stems(
+    mut stems: ResMut<MusicStems>,
+    mut channels: ResMut<DynamicAudioChannels>,
+    audio_assets: Res<AudioAssets>,
+    tracks: Res<Assets<MusicTrack>>,
+) {
+    if stems.volumes.is_some() {
+        return;
+    }
+    let Some(track) = tracks.get(&audio_assets.music) else {
+        return;
+    };
+    // Started together so that they stay in time with each other
+    for (index, stem) in track.stems.iter().enumerate() {
+        let channel = MusicStems::channel(index);
+        if !channels.is_channel(&channel) {
+            channels.create_channel(&channel);
+        }
+        channels
+            .channel(&channel)
+            .play(stem.source.clone())
+            .looped()
+            .with_volume(0.);
+    }
+    stems.volumes = Some(vec![0.; track.stems.len()]);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_danger(
+    time: Res<Time>,
+    mut stems: ResMut<MusicStems>,
+    channels: Res<DynamicAudioChannels>,
+    audio_assets: Res<AudioAssets>,
+    tracks: Res<Assets<MusicTrack>>,
+    health: Res<Health>,
+    power: Res<Power>,
+    rain_intensity: Res<RainIntensity>,
+    volumes: Res<Volumes>,
+) {
+    let (Some(stem_volumes), Some(track)) =
+        (stems.volumes.as_mut(), tracks.get(&audio_assets.music))
+    else {
+        return;
+    };
+    let follow = (MusicStems::FOLLOW_SPEED * time.delta_seconds_f64()).min(1.);
+
+    for (index, (stem, volume)) in track.stems.iter().zip(stem_volumes.iter_mut()).enumerate() {
+        let danger = match stem.danger {
+            Danger::None => 1.,
+            Danger::Health => 1. - health.0 as f32 / Health::MAX as f32,
+            Danger::Power => 1. - power.0,
+            Danger::Rain => rain_intensity.0,
+        };
+        let target = stem.fade(danger) as f64 * stem.volume;
+        *volume += (target - *volume) * follow;
+        channels
+            .channel(&MusicStems::channel(index))
+            .set_volume(*volume * volumes.music);
+    }
+}
+
+fn stop_stems(mut stems: ResMut<MusicStems>, channels: Res<DynamicAudioChannels>) {
+    let Some(volumes) = stems.volumes.take() else {
+        return;
+    };
+    for index in 0..volumes.len() {
+        channels
+            .channel(&MusicStems::channel(index))
+            .stop()
+            .fade_out(AudioTween::linear(FADE_OUT));
+    }
+}
+
+fn pause_stems(stems: Res<MusicStems>, channels: Res<DynamicAudioChannels>) {
+    for index in 0..stems.volumes.as_ref().map_or(0, Vec::len) {
+        channels.channel(&MusicStems::channel(index)).pause();
+    }
+}
+
+fn resume_stems(stems: Res<MusicStems>, channels: Res<DynamicAudioChannels>) {
+    for index in 0..stems.volumes.as_ref().map_or(0, Vec::len) {
+        channels.channel(&MusicStems::channel(index)).resume();
+    }
+}
+
+fn play_sting(
+    mut commands: Commands,
+    audio: Res<Audio>,
+    audio_assets: Res<AudioAssets>,
+    tracks: Res<Assets<MusicTrack>>,
+    volumes: Res<Volumes>,
+) {
+    let Some(sting) = tracks
+        .get(&audio_assets.music)
+        .and_then(|track| track.sting.clone())
+    else {
+        return;
+    };
+    let instance = audio.play(sting).with_volume(volumes.music).handle();
+    commands.insert_resource(Sting(instance));
+}
+
+fn follow_sting_volume(
+    sting: Option<Res<Sting>>,
+    volumes: Res<Volumes>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(sting) = sting else {
+        return;
+    };
+    if let Some(instance) = audio_instances.get_mut(&sting.0) {
+        instance.set_volume(volumes.music, AudioTween::default());
+    }
+}
+
+/// Leaving the game over screen for the menu only happens after the menu music
+/// has started, so the sting is stopped on its own rather than by stopping a
+/// whole channel.
+fn stop_sting(
+    mut commands: Commands,
+    sting: Option<Res<Sting>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(sting) = sting else {
+        return;
+    };
+    if let Some(instance) = audio_instances.get_mut(&sting.0) {
+        instance.stop(AudioTween::linear(FADE_OUT));
+    }
+    commands.remove_resource::<Sting>();
+}
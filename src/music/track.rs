@@ -0,0 +1,129 @@
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::utils::BoxedFuture;
+use bevy_kira_audio::AudioSource;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Layered music as described by a `.music.ron` file in `assets/music`.
+#[derive(Asset, TypePath, Debug)]
+pub struct MusicTrack {
+    pub stems: Vec<Stem>,
+    /// Played once when the game is over.
+    pub sting: Option<Handle<AudioSource>>,
+}
+
+/// One layer of a `MusicTrack`, looping alongside the others and faded in as
+/// its `danger` rises.
+#[derive(Debug)]
+pub struct Stem {
+    pub source: Handle<AudioSource>,
+    pub danger: Danger,
+    /// Level of `danger` at which the stem starts to fade in.
+    pub from: f32,
+    /// Level of `danger` at which the stem is fully faded in.
+    pub to: f32,
+    pub volume: f64,
+}
+
+impl Stem {
+    /// How far the stem is faded in, from 0 to 1, at the given level of danger.
+    pub fn fade(&self, danger: f32) -> f32 {
+        if self.to <= self.from {
+            return if danger >= self.from { 1. } else { 0. };
+        }
+        ((danger - self.from) / (self.to - self.from)).clamp(0., 1.)
+    }
+}
+
+/// What threatens the player, each measured from 0 when all is well to 1
+/// when it is as bad as it gets.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Danger {
+    /// Always at its highest, for stems that play throughout.
+    #[default]
+    None,
+    /// Rises as `Health` is lost.
+    Health,
+    /// Rises as `Power` runs low.
+    Power,
+    /// Rises as more rain falls.
+    Rain,
+}
+
+#[derive(Deserialize)]
+struct MusicTrackFile {
+    stems: Vec<StemFile>,
+    #[serde(default)]
+    sting: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StemFile {
+    path: String,
+    #[serde(default)]
+    danger: Danger,
+    #[serde(default)]
+    from: f32,
+    #[serde(default = "full")]
+    to: f32,
+    #[serde(default = "full_volume")]
+    volume: f64,
+}
+
+fn full() -> f32 {
+    1.
+}
+
+fn full_volume() -> f64 {
+    1.
+}
+
+#[derive(Default)]
+pub struct MusicTrackLoader;
+
+#[derive(Debug, Error)]
+pub enum MusicTrackLoaderError {
+    #[error("Could not read music file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse music file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for MusicTrackLoader {
+    type Asset = MusicTrack;
+    type Settings = ();
+    type Error = MusicTrackLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let file = ron::de::from_bytes::<MusicTrackFile>(&bytes)?;
+            Ok(MusicTrack {
+                stems: file
+                    .stems
+                    .into_iter()
+                    .map(|stem| Stem {
+                        source: load_context.load(stem.path),
+                        danger: stem.danger,
+                        from: stem.from,
+                        to: stem.to,
+                        volume: stem.volume,
+                    })
+                    .collect(),
+                sting: file.sting.map(|path| load_context.load(path)),
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["music.ron"]
+    }
+}
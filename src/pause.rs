@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use crate::{actions::*, app_state::*, ui::*};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), spawn_pause_screen)
+            .add_systems(OnExit(GameState::Paused), despawn_pause_screen)
+            .add_systems(
+                Update,
+                (
+                    toggle_pause
+                        .after(set_actions)
+                        .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+                    click_button.run_if(in_state(GameState::Paused)),
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct PauseScreen;
+
+fn toggle_pause(
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    actions: Res<Actions>,
+) {
+    if !actions.pause.just_pressed() {
+        return;
+    }
+
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+fn spawn_pause_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.),
+                    ..default()
+                },
+                background_color: Color::rgba(0., 0., 0., 0.).into(),
+                ..default()
+            },
+            FadeIn::from_seconds(0.2).with_background_alpha(0.6),
+            PauseScreen,
+        ))
+        .with_children(|children| {
+            for (label, action) in [
+                ("Resume", MenuAction::Resume),
+                ("Restart", MenuAction::Restart),
+                ("Quit to menu", MenuAction::QuitToMenu),
+            ] {
+                children
+                    .spawn((
+                        MenuButtonBundle::default().with_width(Val::Px(260.0)),
+                        action,
+                        FadeIn::from_seconds(0.2),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            MenuButtonLabelBundle::from_text(label).with_alpha(0.),
+                            FadeIn::from_seconds(0.2).with_background_alpha(0.),
+                        ));
+                    });
+            }
+        });
+}
+
+fn despawn_pause_screen(mut commands: Commands, query: Query<Entity, With<PauseScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+enum MenuAction {
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+
+fn click_button(
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<(&Interaction, &MenuAction), (Changed<Interaction>, With<MenuButton>)>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            MenuAction::Resume => {
+                next_game_state.set(GameState::Playing);
+            }
+            MenuAction::Restart => {
+                next_game_state.set(GameState::Restarting);
+            }
+            MenuAction::QuitToMenu => {
+                next_app_state.set(AppState::Menu);
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A resource that is saved whenever it changes and loaded again on startup.
+///
+/// Native builds store it in the platform's config directory, web builds in
+/// `localStorage`.
+pub trait Persistent: Resource + Serialize + DeserializeOwned + Default {
+    /// Name the resource is stored under.
+    const KEY: &'static str;
+    /// Needs to be bumped whenever the format changes in a way that old saves
+    /// can no longer be read. Saves from other versions are discarded.
+    const VERSION: u32;
+}
+
+pub trait PersistenceAppExt {
+    fn init_persistent_resource<T: Persistent>(&mut self) -> &mut Self;
+}
+
+impl PersistenceAppExt for App {
+    fn init_persistent_resource<T: Persistent>(&mut self) -> &mut Self {
+        self.insert_resource(load::<T>().unwrap_or_default())
+            .add_systems(
+                Last,
+                save::<T>.run_if(resource_changed::<T>().and_then(not(resource_added::<T>()))),
+            )
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct SaveRef<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Save<T> {
+    data: T,
+}
+
+fn load<T: Persistent>() -> Option<T> {
+    let contents = storage::read(T::KEY)?;
+    match parse(&contents) {
+        Ok(value) => value,
+        Err(error) => {
+            warn!("Saved {} is corrupt, starting over: {error}", T::KEY);
+            storage::discard(T::KEY);
+            None
+        }
+    }
+}
+
+/// Reads a save, which is `None` if it is from another version and an error
+/// if it can't be read at all.
+fn parse<T: Persistent>(contents: &str) -> Result<Option<T>, ron::error::SpannedError> {
+    ron::from_str::<Header>(contents).and_then(|header| {
+        if header.version == T::VERSION {
+            ron::from_str::<Save<T>>(contents).map(|save| Some(save.data))
+        } else {
+            warn!(
+                "Discarding saved {} from version {} (expected {})",
+                T::KEY,
+                header.version,
+                T::VERSION
+            );
+            Ok(None)
+        }
+    })
+}
+
+fn save<T: Persistent>(value: Res<T>) {
+    let save = SaveRef {
+        version: T::VERSION,
+        data: value.as_ref(),
+    };
+    match ron::ser::to_string_pretty(&save, default()) {
+        Ok(contents) => storage::write(T::KEY, &contents),
+        Err(error) => warn!("Failed to serialize {}: {error}", T::KEY),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use bevy::prelude::*;
+    use directories::ProjectDirs;
+    use std::{fs, path::PathBuf};
+
+    fn path(key: &str) -> Option<PathBuf> {
+        let dirs = ProjectDirs::from("", "", "Acid Rain")?;
+        Some(dirs.config_dir().join(format!("{key}.ron")))
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key)?).ok()
+    }
+
+    pub fn write(key: &str, contents: &str) {
+        let Some(path) = path(key) else {
+            warn!("No config directory to save {key} in");
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if let Err(error) = fs::create_dir_all(parent) {
+                warn!("Failed to create {parent:?}: {error}");
+                return;
+            }
+        }
+        if let Err(error) = fs::write(&path, contents) {
+            warn!("Failed to save {path:?}: {error}");
+        }
+    }
+
+    /// Moves the file out of the way, keeping it around for inspection.
+    pub fn discard(key: &str) {
+        let Some(path) = path(key) else {
+            return;
+        };
+        if let Err(error) = fs::rename(&path, path.with_extension("ron.corrupt")) {
+            warn!("Failed to move away {path:?}: {error}");
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use bevy::prelude::*;
+    use web_sys::Storage;
+
+    fn local_storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn item_key(key: &str) -> String {
+        format!("acid_rain.{key}")
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        local_storage()?.get_item(&item_key(key)).ok()?
+    }
+
+    pub fn write(key: &str, contents: &str) {
+        let Some(storage) = local_storage() else {
+            warn!("No local storage to save {key} in");
+            return;
+        };
+        if storage.set_item(&item_key(key), contents).is_err() {
+            warn!("Failed to save {key} to local storage");
+        }
+    }
+
+    /// Moves the item out of the way, keeping it around for inspection.
+    pub fn discard(key: &str) {
+        let Some(storage) = local_storage() else {
+            return;
+        };
+        if let Ok(Some(contents)) = storage.get_item(&item_key(key)) {
+            let _ = storage.set_item(&item_key(&format!("{key}.corrupt")), &contents);
+        }
+        let _ = storage.remove_item(&item_key(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Serialize, Deserialize, Default, PartialEq, Debug)]
+    struct Counter {
+        count: u32,
+    }
+
+    impl Persistent for Counter {
+        const KEY: &'static str = "counter";
+        const VERSION: u32 = 2;
+    }
+
+    #[test]
+    fn saves_from_this_version_are_loaded() {
+        let counter = parse::<Counter>("(version: 2, data: (count: 3))").unwrap();
+        assert_eq!(counter, Some(Counter { count: 3 }));
+    }
+
+    #[test]
+    fn saves_from_other_versions_are_discarded() {
+        let counter = parse::<Counter>("(version: 1, data: (total: 3))").unwrap();
+        assert_eq!(counter, None);
+    }
+
+    #[test]
+    fn corrupt_saves_are_errors() {
+        assert!(parse::<Counter>("(version: 2, data: (count: ").is_err());
+        assert!(parse::<Counter>("(version: 2, data: (count: -1))").is_err());
+        assert!(parse::<Counter>("not a save").is_err());
+    }
+}
@@ -1,14 +1,17 @@
 use crate::actions::Actions;
 use crate::app_state::*;
-use crate::collider::Collider;
+use crate::broadphase::*;
+use crate::collider::*;
 use crate::color::*;
 use crate::health::Health;
-use crate::level::Level;
+use crate::loading::Textures;
+use crate::power::Power;
 use crate::rain::*;
 use crate::shield::ShieldBundle;
-use crate::velocity::{update_position, Velocity};
+use crate::simulation::Interpolated;
+use crate::sweep::*;
+use crate::velocity::{update_position, ManualPosition, Velocity};
 use bevy::prelude::*;
-use bevy::sprite::collide_aabb::{collide, Collision};
 use bevy::sprite::Anchor;
 
 pub struct PlayerPlugin;
@@ -28,6 +31,107 @@ impl Player {
     }
 }
 
+/// What the player is seen doing, each shown by its own frames of the player
+/// texture.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Pose {
+    Idle,
+    Run,
+    Jump,
+    Fall,
+    Land,
+    Hurt,
+    Death,
+}
+
+impl Pose {
+    fn frames(self) -> &'static [usize] {
+        match self {
+            Pose::Idle => &[0, 1],
+            Pose::Run => &[2, 3, 4, 5],
+            Pose::Jump => &[6],
+            Pose::Fall => &[7],
+            Pose::Land => &[8],
+            Pose::Hurt => &[9],
+            Pose::Death => &[10, 11],
+        }
+    }
+
+    fn frame_seconds(self) -> f32 {
+        match self {
+            Pose::Idle => 0.5,
+            Pose::Death => 0.3,
+            _ => 0.1,
+        }
+    }
+
+    /// Whether the frames start over once they have all been shown, rather
+    /// than staying on the last one.
+    fn looping(self) -> bool {
+        matches!(self, Pose::Idle | Pose::Run)
+    }
+}
+
+/// Picks the pose of a player drawn with the player texture and steps through
+/// its frames.
+#[derive(Component)]
+struct PlayerAnimation {
+    pose: Pose,
+    seconds: f32,
+    /// Time left to show the player flinching from a hit.
+    hurt_seconds: f32,
+    /// Time left to show the player landing, after having been in the air.
+    land_seconds: f32,
+    was_grounded: bool,
+    dead: bool,
+}
+
+impl Default for PlayerAnimation {
+    fn default() -> Self {
+        Self {
+            pose: Pose::Fall,
+            seconds: 0.,
+            hurt_seconds: 0.,
+            land_seconds: 0.,
+            was_grounded: false,
+            dead: false,
+        }
+    }
+}
+
+impl PlayerAnimation {
+    const HURT_SECONDS: f32 = 0.3;
+    const LAND_SECONDS: f32 = 0.12;
+    /// How fast the player needs to move to be running rather than standing.
+    const RUNNING_SPEED: f32 = 20.;
+
+    fn next_pose(&self, jump_state: &JumpState, velocity: Vec2) -> Pose {
+        if self.dead {
+            return Pose::Death;
+        }
+        if self.hurt_seconds > 0. {
+            return Pose::Hurt;
+        }
+        match jump_state {
+            JumpState::Jumping(_) => Pose::Jump,
+            JumpState::Falling => Pose::Fall,
+            JumpState::Grounded if self.land_seconds > 0. => Pose::Land,
+            JumpState::Grounded if velocity.x.abs() > Self::RUNNING_SPEED => Pose::Run,
+            JumpState::Grounded => Pose::Idle,
+        }
+    }
+
+    fn frame(&self) -> usize {
+        let frames = self.pose.frames();
+        let step = (self.seconds / self.pose.frame_seconds()) as usize;
+        if self.pose.looping() {
+            frames[step % frames.len()]
+        } else {
+            frames[step.min(frames.len() - 1)]
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub enum JumpState {
     Grounded,
@@ -40,43 +144,71 @@ pub enum JumpState {
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnExit(GameState::GameOver), despawn_player)
+            .add_systems(OnExit(GameState::LevelComplete), despawn_player)
+            .add_systems(OnExit(GameState::Restarting), despawn_player)
             .add_systems(OnExit(AppState::InGame), despawn_player)
+            .add_systems(
+                FixedUpdate,
+                update_velocity
+                    .before(update_position)
+                    .run_if(in_state(GameState::Playing)),
+            )
             .add_systems(
                 Update,
-                (
-                    update_velocity.before(update_position),
-                    fade_out_damage.before(get_hit_by_rain),
-                    get_hit_by_rain.after(splash_rain),
-                )
+                (fade_out_damage, get_hit_by_rain)
+                    .chain()
                     .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), kill_player)
+            // Keeps going on the game over screen to show the player dying
+            .add_systems(
+                Update,
+                animate_player
+                    .after(get_hit_by_rain)
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::GameOver))),
             );
     }
 }
 
-pub fn spawn_player(mut commands: Commands, translation: Vec3) {
+pub fn spawn_player(mut commands: Commands, translation: Vec3, textures: &Textures) {
+    let atlas = textures.player();
     let player = (
-        SpriteBundle {
-            sprite: Sprite {
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
                 color: Player::COLOR_BASE,
                 custom_size: Some(Player::SIZE),
                 anchor: Anchor::BottomCenter,
                 ..default()
             },
+            texture_atlas: atlas.clone().unwrap_or_else(|| textures.blank_atlas()),
             transform: Transform::from_translation(translation),
             ..default()
         },
         Velocity(Vec2::ZERO),
-        Collider::from_center_size(Player::local_center(), Player::SIZE),
+        Interpolated::new(translation),
+        ManualPosition,
+        Collider::from_center_size(Player::local_center(), Player::SIZE).with_layers(
+            CollisionLayers::PLAYER,
+            CollisionLayers::TERRAIN | CollisionLayers::RAIN,
+        ),
         RainHitListener,
         Player {
             jump_state: JumpState::Falling,
         },
     );
 
-    commands.spawn(player).with_children(|commands| {
-        commands.spawn(ShieldBundle::new(Transform::from_translation(
-            Player::local_center().extend(-10.),
-        )));
+    let mut player_commands = commands.spawn(player);
+    // Flat colored players only have the one frame
+    if atlas.is_some() {
+        player_commands.insert(PlayerAnimation::default());
+    }
+    player_commands.with_children(|commands| {
+        commands.spawn(
+            ShieldBundle::new(Transform::from_translation(
+                Player::local_center().extend(-10.),
+            ))
+            .with_texture(textures.shield()),
+        );
     });
 }
 
@@ -86,7 +218,8 @@ fn despawn_player(mut commands: Commands, player_query: Query<Entity, With<Playe
     }
 }
 
-const X_SPEED: f32 = 200.;
+// Doubled since `ManualPosition` stopped velocity being applied twice a tick
+const X_SPEED: f32 = 400.;
 const ACCELERATION_X: f32 = 10.;
 const DECELERATION_X: f32 = 15.;
 
@@ -94,7 +227,8 @@ fn update_velocity(
     time: Res<Time>,
     actions: Res<Actions>,
     mut player_query: Query<(&mut Velocity, &mut Transform, &Collider, &mut Player)>,
-    level_query: Query<(&GlobalTransform, &Collider), (With<Level>, Without<Player>)>,
+    obstacle_query: Query<&Collider, Without<Player>>,
+    collider_grid: Res<ColliderGrid>,
 ) {
     let player_movement = actions.player_movement.unwrap_or(Vec2::ZERO);
     let delta = time.delta_seconds();
@@ -110,50 +244,43 @@ fn update_velocity(
             delta,
         );
         let mut new_velocity = Vec2::new(new_velocity_x, new_velocity_y);
-        let mut new_transform = player_transform
-            .with_translation(player_transform.translation + new_velocity.extend(0.) * delta);
-        let mut is_on_ground = false;
-
-        for (level_transform, level_collider) in level_query.iter() {
-            let player_rect = player_collider.rect(&new_transform.translation);
-            let level_rect = level_collider.rect(&level_transform.translation());
-            let collision = collide(
-                player_rect.center().extend(0.),
-                player_rect.size(),
-                level_rect.center().extend(0.),
-                level_rect.size(),
-            );
-            match collision {
-                None => continue,
-                Some(Collision::Top) | Some(Collision::Inside) => {
-                    if let JumpState::Jumping(_) = new_jump_state {
-                        continue;
-                    }
-                    new_velocity.y = 0.;
-                    new_jump_state = JumpState::Grounded;
-                    is_on_ground = true;
-                    new_transform.translation.y = level_rect.max.y;
-                }
-                Some(Collision::Bottom) => {
-                    new_velocity.y = 0.;
-                    new_transform.translation.y = level_rect.min.y - Player::SIZE.y;
-                }
-                Some(Collision::Left) => {
-                    new_velocity.x = 0.;
-                    new_transform.translation.x = level_rect.min.x - Player::SIZE.x / 2.;
-                }
-                Some(Collision::Right) => {
-                    new_velocity.x = 0.;
-                    new_transform.translation.x = level_rect.max.x + Player::SIZE.x / 2.;
-                }
-            }
-        }
+        let offset = new_velocity * delta;
+        let player_rect = player_collider.rect(&player_transform.translation);
+        // Stay on the ground when walking down slopes as steep as 45 degrees
+        let snap_down = if new_jump_state == JumpState::Grounded {
+            offset.x.abs()
+        } else {
+            0.
+        };
+        let mut swept_rect = player_rect.union(Rect::from_center_size(
+            player_rect.center() + offset,
+            player_rect.size(),
+        ));
+        swept_rect.min.y -= snap_down;
+        let obstacles: Vec<Obstacle> = collider_grid
+            .query(swept_rect)
+            .filter_map(|(entity, rect)| Some((obstacle_query.get(entity).ok()?, rect)))
+            .filter(|(obstacle_collider, _)| {
+                obstacle_collider.interacts_with(player_collider.layers, player_collider.mask)
+            })
+            .map(|(obstacle_collider, rect)| Obstacle::from_collider(obstacle_collider, rect))
+            .collect();
+        let movement = move_and_collide(player_rect, offset, &obstacles, snap_down);
 
-        if !is_on_ground && player.jump_state == JumpState::Grounded {
-            new_jump_state = JumpState::Falling;
+        if movement.normal_x().is_some() {
+            new_velocity.x = 0.;
+        }
+        match movement.normal_y() {
+            Some(normal) if normal.y > 0. => {
+                new_velocity.y = 0.;
+                new_jump_state = JumpState::Grounded;
+            }
+            Some(_) => new_velocity.y = 0.,
+            None if new_jump_state == JumpState::Grounded => new_jump_state = JumpState::Falling,
+            None => {}
         }
 
-        *player_transform = new_transform;
+        player_transform.translation += movement.offset.extend(0.);
         player_velocity.0 = new_velocity;
         player.jump_state = new_jump_state;
     }
@@ -174,10 +301,11 @@ fn get_velocity_x(velocity_x: f32, movement_x: f32, delta: f32) -> f32 {
     new_vel_x
 }
 
-const JUMP_SPEED: f32 = 400.;
-const JUMP_GRAVITY: f32 = -1000.;
-const FALL_SPEED: f32 = 400.;
-const FALL_GRAVITY: f32 = -1000.;
+// Doubled along with `X_SPEED`, see above.
+const JUMP_SPEED: f32 = 800.;
+const JUMP_GRAVITY: f32 = -2000.;
+const FALL_SPEED: f32 = 800.;
+const FALL_GRAVITY: f32 = -2000.;
 
 fn get_velocity_y(
     velocity_y: f32,
@@ -190,7 +318,8 @@ fn get_velocity_y(
             if movement_y > 0. {
                 (JUMP_SPEED, JumpState::Jumping(1.))
             } else {
-                (-1., JumpState::Grounded)
+                // Doubled along with `X_SPEED` too
+                (-2., JumpState::Grounded)
             }
         }
         JumpState::Jumping(jump_power) => {
@@ -219,25 +348,32 @@ fn get_velocity_y(
 
 fn get_hit_by_rain(
     mut rain_hit: EventReader<RainHit>,
-    mut player_query: Query<(&mut Sprite, Entity), With<Player>>,
+    mut player_query: Query<(&mut TextureAtlasSprite, Entity), With<Player>>,
     mut health: ResMut<Health>,
+    mut power: ResMut<Power>,
     mut playing_state: ResMut<NextState<GameState>>,
 ) {
     for (mut player_sprite, player_entity) in player_query.iter_mut() {
-        for RainHit(entity) in rain_hit.read() {
-            if player_entity == *entity {
-                if health.0 > 0 {
-                    health.0 -= 1;
-                    player_sprite.color = Player::COLOR_HIT;
-                } else {
-                    playing_state.set(GameState::GameOver);
-                }
+        for RainHit(entity, kind) in rain_hit.read() {
+            if player_entity != *entity {
+                continue;
+            }
+            if kind.damage() == 0 {
+                power.0 = (power.0 + kind.power()).min(1.);
+            } else if health.0 > 0 {
+                health.0 = health.0.saturating_sub(kind.damage());
+                player_sprite.color = Player::COLOR_HIT;
+            } else {
+                playing_state.set(GameState::GameOver);
             }
         }
     }
 }
 
-fn fade_out_damage(time: Res<Time>, mut player_query: Query<&mut Sprite, With<Player>>) {
+fn fade_out_damage(
+    time: Res<Time>,
+    mut player_query: Query<&mut TextureAtlasSprite, With<Player>>,
+) {
     let delta = time.delta_seconds();
     for mut player_sprite in player_query.iter_mut() {
         if player_sprite.color != Player::COLOR_BASE {
@@ -253,3 +389,61 @@ fn fade_out_damage(time: Res<Time>, mut player_query: Query<&mut Sprite, With<Pl
         }
     }
 }
+
+fn kill_player(mut animation_query: Query<&mut PlayerAnimation>) {
+    for mut animation in animation_query.iter_mut() {
+        animation.dead = true;
+    }
+}
+
+fn animate_player(
+    time: Res<Time>,
+    mut rain_hit: EventReader<RainHit>,
+    mut player_query: Query<(
+        Entity,
+        &mut TextureAtlasSprite,
+        &mut PlayerAnimation,
+        &Player,
+        &Velocity,
+    )>,
+) {
+    let hurt: Vec<Entity> = rain_hit
+        .read()
+        .filter(|RainHit(_, kind)| kind.damage() > 0)
+        .map(|RainHit(entity, _)| *entity)
+        .collect();
+    let delta = time.delta_seconds();
+
+    for (entity, mut sprite, mut animation, player, velocity) in player_query.iter_mut() {
+        if hurt.contains(&entity) {
+            animation.hurt_seconds = PlayerAnimation::HURT_SECONDS;
+        }
+        let grounded = player.jump_state == JumpState::Grounded;
+        if grounded && !animation.was_grounded {
+            animation.land_seconds = PlayerAnimation::LAND_SECONDS;
+        }
+        animation.was_grounded = grounded;
+
+        let pose = animation.next_pose(&player.jump_state, velocity.0);
+        if pose == animation.pose {
+            animation.seconds += delta;
+        } else {
+            animation.pose = pose;
+            animation.seconds = 0.;
+        }
+        animation.hurt_seconds = (animation.hurt_seconds - delta).max(0.);
+        animation.land_seconds = (animation.land_seconds - delta).max(0.);
+
+        let frame = animation.frame();
+        if sprite.index != frame {
+            sprite.index = frame;
+        }
+        let facing_left = velocity.0.x < 0.;
+        if !animation.dead
+            && velocity.0.x.abs() > PlayerAnimation::RUNNING_SPEED
+            && sprite.flip_x != facing_left
+        {
+            sprite.flip_x = facing_left;
+        }
+    }
+}
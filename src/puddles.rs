@@ -0,0 +1,310 @@
+use crate::app_state::*;
+use crate::broadphase::ColliderGrid;
+use crate::collider::{Collider, CollisionLayers};
+use crate::drops::DropKind;
+use crate::level::Level;
+use crate::loading::Textures;
+use crate::player::Player;
+use crate::rain::*;
+use crate::weather::{Weather, WeatherDirector};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::utils::HashMap;
+
+/// Pools the acid that splashes onto the tops of level blocks into puddles,
+/// which burn the player standing in them.
+pub struct PuddlePlugin;
+
+impl Plugin for PuddlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Burns>()
+            .add_systems(OnEnter(GameState::Playing), reset_burns)
+            .add_systems(
+                FixedUpdate,
+                (
+                    collect_acid.after(splash_rain),
+                    overflow_puddles,
+                    evaporate_puddles,
+                    burn_player,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, show_puddles.run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(GameState::GameOver), despawn_puddles)
+            .add_systems(OnExit(GameState::LevelComplete), despawn_puddles)
+            .add_systems(OnExit(GameState::Restarting), despawn_puddles)
+            .add_systems(OnExit(AppState::InGame), despawn_puddles);
+    }
+}
+
+/// Burn damage each player has taken from puddles that doesn't add up to a
+/// whole hit yet.
+#[derive(Resource, Default)]
+struct Burns(HashMap<Entity, f32>);
+
+/// A puddle of acid on one stretch of the top of a level block.
+#[derive(Component)]
+pub struct Puddle {
+    surface: Entity,
+    segment: i32,
+    /// How full the puddle is, from 0 to 1.
+    acid: f32,
+    /// Acid that has overflowed off the edge of the block but not dripped yet.
+    dripping: f32,
+}
+
+impl Puddle {
+    const COLOR: Color = Color::rgba(0.3, 1., 0.7, 0.6);
+    /// How wide the stretches of a block's top that puddles cover are.
+    const WIDTH: f32 = 40.;
+    const MAX_HEIGHT: f32 = 6.;
+    /// How much of a drop's damage ends up in a puddle.
+    const ACID_PER_DAMAGE: f32 = 0.0005;
+    const EVAPORATION_PER_SECOND: f32 = 0.002;
+    const LULL_EVAPORATION_PER_SECOND: f32 = 0.1;
+    /// Damage dealt per second to a player standing in a full puddle.
+    const BURN_PER_SECOND: f32 = 4.;
+    /// How much overflowing acid makes up a drop dripping off an edge.
+    const DRIP_ACID: f32 = 0.01;
+    const DRIP_SPEED: f32 = 300.;
+
+    fn new(surface: Entity, segment: i32, acid: f32) -> Self {
+        Self {
+            surface,
+            segment,
+            acid,
+            dripping: 0.,
+        }
+    }
+
+    fn segment_count(surface: &Rect) -> i32 {
+        (surface.width() / Self::WIDTH).ceil() as i32
+    }
+
+    /// The stretch of the top of `surface` that the puddle covers, as tall as
+    /// the puddle is deep.
+    fn rect(&self, surface: &Rect) -> Rect {
+        let min_x = surface.min.x + self.segment as f32 * Self::WIDTH;
+        Rect::new(
+            min_x,
+            surface.max.y,
+            (min_x + Self::WIDTH).min(surface.max.x),
+            surface.max.y + self.acid * Self::MAX_HEIGHT,
+        )
+    }
+}
+
+fn collect_acid(
+    mut commands: Commands,
+    mut rain_splash: EventReader<RainSplash>,
+    mut puddle_query: Query<(Entity, &mut Puddle)>,
+    level_query: Query<(&GlobalTransform, &Collider), With<Level>>,
+) {
+    let mut collected: HashMap<(Entity, i32), f32> = HashMap::default();
+    for splash in rain_splash.read() {
+        if splash.kind == DropKind::Water {
+            continue;
+        }
+        let Ok((level_transform, level_collider)) = level_query.get(splash.target) else {
+            continue;
+        };
+        let surface = level_collider.rect(&level_transform.translation());
+        // Only flat tops hold puddles
+        if (splash.point.y - surface.max.y).abs() > 0.5 {
+            continue;
+        }
+
+        let segment = ((splash.point.x - surface.min.x) / Puddle::WIDTH).floor() as i32;
+        let segment = segment.clamp(0, Puddle::segment_count(&surface) - 1);
+        *collected.entry((splash.target, segment)).or_default() +=
+            splash.kind.damage() as f32 * Puddle::ACID_PER_DAMAGE;
+    }
+
+    for (_, mut puddle) in puddle_query.iter_mut() {
+        if let Some(acid) = collected.remove(&(puddle.surface, puddle.segment)) {
+            puddle.acid += acid;
+        }
+    }
+    for ((surface, segment), acid) in collected {
+        spawn_puddle(
+            &mut commands,
+            &level_query,
+            Puddle::new(surface, segment, acid),
+        );
+    }
+}
+
+fn spawn_puddle(
+    commands: &mut Commands,
+    level_query: &Query<(&GlobalTransform, &Collider), With<Level>>,
+    puddle: Puddle,
+) {
+    let Ok((level_transform, level_collider)) = level_query.get(puddle.surface) else {
+        return;
+    };
+    let rect = puddle.rect(&level_collider.rect(&level_transform.translation()));
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Puddle::COLOR,
+                custom_size: Some(Vec2::new(rect.width(), 0.)),
+                anchor: Anchor::BottomCenter,
+                ..default()
+            },
+            transform: Transform::from_xyz(rect.center().x, rect.min.y, 0.5),
+            ..default()
+        },
+        // As tall as a full puddle, since how deep it is only changes how much it burns
+        Collider::from_center_size(
+            Vec2::new(0., Puddle::MAX_HEIGHT / 2.),
+            Vec2::new(rect.width(), Puddle::MAX_HEIGHT),
+        )
+        .with_layers(CollisionLayers::HAZARDS, CollisionLayers::PLAYER),
+        puddle,
+    ));
+}
+
+/// Spreads acid from full puddles towards the nearest edge of their block,
+/// and drips it off once it gets there.
+fn overflow_puddles(
+    mut commands: Commands,
+    mut puddle_query: Query<(Entity, &mut Puddle)>,
+    level_query: Query<(&GlobalTransform, &Collider), With<Level>>,
+    settings: Res<RainSettings>,
+    textures: Textures,
+) {
+    let mut overflowed: HashMap<(Entity, i32), f32> = HashMap::default();
+    for (_, mut puddle) in puddle_query.iter_mut() {
+        if puddle.acid <= 1. {
+            continue;
+        }
+        let Ok((level_transform, level_collider)) = level_query.get(puddle.surface) else {
+            continue;
+        };
+        let surface = level_collider.rect(&level_transform.translation());
+        let excess = puddle.acid - 1.;
+        puddle.acid = 1.;
+
+        let rect = puddle.rect(&surface);
+        let direction = if rect.center().x < surface.center().x {
+            -1
+        } else {
+            1
+        };
+        let next_segment = puddle.segment + direction;
+        if (0..Puddle::segment_count(&surface)).contains(&next_segment) {
+            *overflowed
+                .entry((puddle.surface, next_segment))
+                .or_default() += excess;
+            continue;
+        }
+
+        puddle.dripping += excess;
+        while puddle.dripping >= Puddle::DRIP_ACID {
+            puddle.dripping -= Puddle::DRIP_ACID;
+            spawn_drip(
+                &mut commands,
+                surface,
+                direction as f32,
+                Puddle::DRIP_SPEED.min(settings.speed),
+                textures.drop(),
+            );
+        }
+    }
+
+    let puddles: HashMap<(Entity, i32), Entity> = puddle_query
+        .iter()
+        .map(|(entity, puddle)| ((puddle.surface, puddle.segment), entity))
+        .collect();
+    for ((surface, segment), excess) in overflowed {
+        match puddles.get(&(surface, segment)) {
+            Some(&entity) => {
+                if let Ok((_, mut puddle)) = puddle_query.get_mut(entity) {
+                    puddle.acid += excess;
+                }
+            }
+            None => spawn_puddle(
+                &mut commands,
+                &level_query,
+                Puddle::new(surface, segment, excess),
+            ),
+        }
+    }
+}
+
+fn evaporate_puddles(
+    mut commands: Commands,
+    time: Res<Time>,
+    director: Res<WeatherDirector>,
+    mut puddle_query: Query<(Entity, &mut Puddle)>,
+    level_query: Query<(), With<Level>>,
+) {
+    let evaporation = if director.current() == Some(Weather::Lull) {
+        Puddle::LULL_EVAPORATION_PER_SECOND
+    } else {
+        Puddle::EVAPORATION_PER_SECOND
+    } * time.delta_seconds();
+
+    for (entity, mut puddle) in puddle_query.iter_mut() {
+        puddle.acid -= evaporation;
+        // Puddles go along with the block they're on when it crumbles
+        if puddle.acid <= 0. || !level_query.contains(puddle.surface) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn burn_player(
+    time: Res<Time>,
+    collider_grid: Res<ColliderGrid>,
+    puddle_query: Query<(&Puddle, &Collider)>,
+    player_query: Query<(Entity, &Transform, &Collider), With<Player>>,
+    mut rain_hit_writer: EventWriter<RainHit>,
+    mut burns: ResMut<Burns>,
+) {
+    for (player_entity, player_transform, player_collider) in player_query.iter() {
+        let feet = player_collider.rect(&player_transform.translation);
+        let deepest = collider_grid
+            .query(feet.inset(1.))
+            .filter_map(|(entity, rect)| Some((puddle_query.get(entity).ok()?, rect)))
+            // Only the hazards that hurt the player, whatever else it collides with
+            .filter(|((_, collider), _)| {
+                collider.interacts_with(player_collider.layers, CollisionLayers::HAZARDS)
+            })
+            .filter(|(_, rect)| {
+                feet.min.x < rect.max.x
+                    && feet.max.x > rect.min.x
+                    && feet.min.y >= rect.min.y - 1.
+                    && feet.min.y <= rect.max.y + 1.
+            })
+            .map(|((puddle, _), _)| puddle.acid)
+            .fold(0., f32::max);
+
+        let burn = burns.0.entry(player_entity).or_default();
+        *burn += deepest * Puddle::BURN_PER_SECOND * time.delta_seconds();
+        // Burns hurt the same way as acid drops do
+        while *burn >= 1. {
+            *burn -= 1.;
+            rain_hit_writer.send(RainHit(player_entity, DropKind::Acid));
+        }
+    }
+}
+
+fn reset_burns(mut burns: ResMut<Burns>) {
+    burns.0.clear();
+}
+
+fn show_puddles(mut puddle_query: Query<(&Puddle, &mut Sprite), Changed<Puddle>>) {
+    for (puddle, mut sprite) in puddle_query.iter_mut() {
+        if let Some(size) = sprite.custom_size.as_mut() {
+            size.y = puddle.acid.clamp(0., 1.) * Puddle::MAX_HEIGHT;
+        }
+    }
+}
+
+fn despawn_puddles(mut commands: Commands, puddle_query: Query<Entity, With<Puddle>>) {
+    for entity in puddle_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,19 +1,24 @@
 use std::f32::consts::{FRAC_PI_2, PI};
 
-use crate::{app_state::*, collider::Collider, velocity::*};
-use bevy::{
-    prelude::*,
-    sprite::{collide_aabb::*, Anchor},
+use crate::{
+    app_state::*, args::arg_value, broadphase::*, collider::*, drops::*, loading::Textures,
+    simulation::*, velocity::*, wind::Wind,
 };
+use bevy::{prelude::*, sprite::Anchor};
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
 
 pub struct RainPlugin;
 
 #[derive(Component)]
-pub struct Rain(RainState);
+pub struct Rain {
+    state: RainState,
+    kind: DropKind,
+}
 
 impl Rain {
-    const COLOR: Color = Color::rgb(0.3, 1., 0.7);
+    const LAYERS: CollisionLayers = CollisionLayers::RAIN;
 }
 
 #[derive(PartialEq, Debug)]
@@ -25,15 +30,25 @@ enum RainState {
 impl Plugin for RainPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<RainHit>()
+            .add_event::<RainSplash>()
+            .init_resource::<RainSettings>()
+            .insert_resource(SeedOverride(arg_value("--seed")))
+            .insert_resource(RainRng::from_seed(thread_rng().gen()))
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     spawn_rain,
-                    (splash_rain, despawn_finished_rain).after(update_position),
+                    (
+                        splash_rain.after(rebuild_collider_grid),
+                        despawn_finished_rain,
+                    )
+                        .after(update_position),
                 )
                     .run_if(in_state(GameState::Playing)),
             )
             .add_systems(OnExit(GameState::GameOver), despawn_rain)
+            .add_systems(OnExit(GameState::LevelComplete), despawn_rain)
+            .add_systems(OnExit(GameState::Restarting), despawn_rain)
             .add_systems(OnExit(AppState::InGame), despawn_rain);
     }
 }
@@ -43,145 +58,396 @@ const ANGLE: f32 = -1.4;
 const SPEED: f32 = 800.;
 const SIZE: Vec2 = Vec2::new(8., 12.);
 
-fn spawn_rain(
+/// How the rain falls in the current level.
+#[derive(Resource, Deserialize, Clone, Debug)]
+pub struct RainSettings {
+    pub density: f32,
+    pub angle: f32,
+    pub speed: f32,
+    /// Fixed seed for the rain in this level, a random one is picked per run if unset.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub drops: DropMix,
+}
+
+impl Default for RainSettings {
+    fn default() -> Self {
+        Self {
+            density: DENSITY,
+            angle: ANGLE,
+            speed: SPEED,
+            seed: None,
+            drops: DropMix::default(),
+        }
+    }
+}
+
+/// Seed given on the command line as `--seed <number>`, which takes
+/// precedence over the seed of every level.
+#[derive(Resource)]
+pub struct SeedOverride(pub Option<u64>);
+
+/// The source of all randomness in the rain and its splashes, so that a run
+/// can be replayed exactly from its seed.
+#[derive(Resource)]
+pub struct RainRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl RainRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Seeds a new run, preferring the command line seed over the level's own.
+    pub fn for_run(settings: &RainSettings, seed_override: &SeedOverride) -> Self {
+        let seed = seed_override
+            .0
+            .or(settings.seed)
+            .unwrap_or_else(|| thread_rng().gen());
+        info!("Rain seed: {seed}");
+        Self::from_seed(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rng(&mut self) -> &mut ChaCha8Rng {
+        &mut self.rng
+    }
+}
+
+pub fn spawn_rain(
     mut commands: Commands,
     time: Res<Time>,
+    settings: Res<RainSettings>,
+    wind: Res<Wind>,
+    mut rain_rng: ResMut<RainRng>,
     camera_query: Query<&OrthographicProjection>,
+    textures: Textures,
 ) {
-    let mut rng = thread_rng();
-    let camera_projection = camera_query.single();
+    let rng = &mut rain_rng.rng;
+    let texture = textures.drop();
+    let area = camera_query.single().area;
+    let angle = settings.angle + wind.angle();
+    // Start upwind of the screen far enough for the rain to cover all of it
+    let direction = Vec2::from_angle(angle);
+    let drift = area.height() * direction.x / -direction.y.min(-0.1);
+    let margin = drift.abs().max(200.);
+    let spawn_x = if drift >= 0. {
+        (area.min.x - margin)..area.max.x
+    } else {
+        area.min.x..(area.max.x + margin)
+    };
 
-    for _ in 0..(DENSITY * time.delta_seconds() * 60.).round() as u32 {
-        commands
-            .spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: Rain::COLOR,
-                    custom_size: Some(Vec2::new(rng.gen_range(4.0..=16.0), 1.)),
-                    ..default()
-                },
-                transform: Transform::from_rotation(Quat::from_rotation_z(ANGLE)).with_translation(
-                    Vec3::new(
-                        rng.gen_range(
-                            (camera_projection.area.min.x - 200.)..camera_projection.area.max.x,
-                        ),
-                        camera_projection.area.max.y,
-                        2.,
-                    ),
-                ),
-                ..default()
-            })
-            .insert(Anchor::CenterRight)
-            .insert(Velocity(Vec2::from_angle(ANGLE) * SPEED))
-            .insert(Rain(RainState::Falling));
+    for _ in 0..(settings.density * time.delta_seconds() * 60.).round() as u32 {
+        let kind = settings.drops.pick(rng.gen());
+        let translation = Vec3::new(rng.gen_range(spawn_x.clone()), area.max.y, 2.);
+        let length = rng.gen_range(kind.length());
+        spawn_drop(
+            &mut commands,
+            kind,
+            translation,
+            length,
+            direction * settings.speed * kind.speed(),
+            texture.clone(),
+        );
     }
 }
 
+/// Spawns a drop of acid dripping straight down off the top left or right
+/// corner of `surface`, depending on `direction`. It starts out far enough
+/// from the corner to not hit `surface` on its way down.
+pub fn spawn_drip(
+    commands: &mut Commands,
+    surface: Rect,
+    direction: f32,
+    speed: f32,
+    texture: Option<Handle<Image>>,
+) {
+    let x = if direction < 0. {
+        surface.min.x - SIZE.x / 2. - 1.
+    } else {
+        surface.max.x + SIZE.x / 2. + 1.
+    };
+    let translation = Vec3::new(x, surface.max.y - SIZE.y / 2., 2.);
+    spawn_drop(
+        commands,
+        DropKind::Acid,
+        translation,
+        *DropKind::Acid.length().start(),
+        Vec2::NEG_Y * speed,
+        texture,
+    );
+}
+
+fn spawn_drop(
+    commands: &mut Commands,
+    kind: DropKind,
+    translation: Vec3,
+    length: f32,
+    velocity: Vec2,
+    texture: Option<Handle<Image>>,
+) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: kind.color(),
+                custom_size: Some(Vec2::new(length, kind.thickness())),
+                ..default()
+            },
+            texture: texture.unwrap_or_default(),
+            transform: Transform::from_rotation(Quat::from_rotation_z(
+                velocity.y.atan2(velocity.x),
+            ))
+            .with_translation(translation),
+            ..default()
+        })
+        .insert(Anchor::CenterRight)
+        .insert(Velocity(velocity))
+        .insert(Interpolated::new(translation))
+        .insert(Rain {
+            state: RainState::Falling,
+            kind,
+        });
+}
+
 #[derive(Component)]
 pub struct RainHitListener;
 
+/// Sent when a drop of rain of the given kind hits a `RainHitListener`.
 #[derive(Event)]
-pub struct RainHit(pub Entity);
+pub struct RainHit(pub Entity, pub DropKind);
 
+/// Sent when a drop of rain splashes on top of something.
+#[derive(Event)]
+pub struct RainSplash {
+    pub target: Entity,
+    pub point: Vec2,
+    pub kind: DropKind,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn splash_rain(
     mut rain_query: Query<(&mut Rain, &mut Velocity, &mut Transform)>,
-    target_query: Query<
-        (
-            &GlobalTransform,
-            &Collider,
-            Entity,
-            Option<&RainHitListener>,
-        ),
-        Without<Rain>,
-    >,
+    target_query: Query<(&Collider, Option<&RainHitListener>), Without<Rain>>,
+    collider_grid: Res<ColliderGrid>,
+    time: Res<Time>,
     mut rain_hit_writer: EventWriter<RainHit>,
+    mut rain_splash_writer: EventWriter<RainSplash>,
+    settings: Res<RainSettings>,
+    mut rain_rng: ResMut<RainRng>,
 ) {
-    let rng = &mut thread_rng();
+    let rng = &mut rain_rng.rng;
 
     for (mut rain, mut rain_velocity, mut rain_transform) in rain_query.iter_mut() {
-        if rain.0 == RainState::Splashing {
+        if rain.state == RainState::Splashing {
             rain_transform.scale.y *= 0.7;
             continue;
         }
 
-        let rain_translation = rain_transform.translation;
-        for (target_transform, target_collider, target_entity, hit_listener) in target_query.iter()
+        let rain_to = rain_transform.translation.truncate();
+        let rain_from = rain_to - rain_velocity.0 * time.delta_seconds();
+        let rain_rect = Rect::from_corners(rain_from, rain_to).inset(SIZE.max_element() / 2.);
+        for (target_entity, target_rect, (target_collider, hit_listener)) in collider_grid
+            .query(rain_rect)
+            .filter_map(|(entity, rect)| Some((entity, rect, target_query.get(entity).ok()?)))
         {
-            if !target_collider.solid {
+            if !target_collider.interacts_with(Rain::LAYERS, rain.kind.mask()) {
                 continue;
             }
 
-            let target_rect = target_collider.rect(&target_transform.translation());
-            let Some(target_collision) = collide(
-                rain_translation,
-                SIZE,
-                target_rect.center().extend(0.),
-                target_collider.size,
-            ) else {
+            let Some(impact) = impact(target_collider, &target_rect, rain_from, rain_to) else {
                 continue;
             };
 
-            handle_collision(
-                target_collision,
+            if let Impact::Surface { point, normal } = impact {
+                if normal.y > 0. {
+                    rain_splash_writer.send(RainSplash {
+                        target: target_entity,
+                        point,
+                        kind: rain.kind,
+                    });
+                }
+            }
+            handle_impact(
+                impact,
                 rng,
-                &target_rect,
+                &settings,
                 (&mut rain, &mut rain_velocity, &mut rain_transform),
             );
 
             if hit_listener.is_some() {
-                rain_hit_writer.send(RainHit(target_entity));
+                rain_hit_writer.send(RainHit(target_entity, rain.kind));
             }
             break;
         }
     }
 }
 
-fn handle_collision(
-    collision: Collision,
-    rng: &mut ThreadRng,
-    rect: &Rect,
+/// Where a raindrop hit a collider.
+enum Impact {
+    /// On a surface, which it splashes against.
+    Surface { point: Vec2, normal: Vec2 },
+    /// On the side of a wall at `x`, which it runs down along, leaning away
+    /// from the wall in `direction`.
+    Side { x: f32, direction: f32 },
+}
+
+/// How a raindrop moving from `from` to `to` hit `collider`, if it did.
+fn impact(collider: &Collider, rect: &Rect, from: Vec2, to: Vec2) -> Option<Impact> {
+    let impact = match collider.shape {
+        ColliderShape::Circle => {
+            let radius = rect.size().min_element() / 2.;
+            let offset = to - rect.center();
+            if offset.length() > radius {
+                return None;
+            }
+            let normal = offset.try_normalize().unwrap_or(Vec2::Y);
+            Impact::Surface {
+                point: rect.center() + normal * radius,
+                normal,
+            }
+        }
+        shape => {
+            let reach = Rect {
+                min: rect.min - SIZE / 2.,
+                max: rect.max + SIZE / 2.,
+            };
+            // Drops that start inside the reach, like those running down a
+            // wall, don't enter it through a side again
+            let entry_normal = entry_normal(from, to, &reach).unwrap_or(Vec2::ZERO);
+            let has_left_wall = matches!(shape, ColliderShape::Box | ColliderShape::SlopeUpLeft);
+            let has_right_wall = matches!(shape, ColliderShape::Box | ColliderShape::SlopeUpRight);
+            if entry_normal.x < 0. && has_left_wall && to.y < shape.surface_y(rect, rect.min.x) {
+                Impact::Side {
+                    x: rect.min.x,
+                    direction: -1.,
+                }
+            } else if entry_normal.x > 0.
+                && has_right_wall
+                && to.y < shape.surface_y(rect, rect.max.x)
+            {
+                Impact::Side {
+                    x: rect.max.x,
+                    direction: 1.,
+                }
+            } else if entry_normal.y < 0. {
+                Impact::Surface {
+                    point: Vec2::new(to.x.clamp(rect.min.x, rect.max.x), rect.min.y),
+                    normal: Vec2::NEG_Y,
+                }
+            } else {
+                // The top is hit by drops crossing it, wherever they started
+                let x = to.x.clamp(rect.min.x, rect.max.x);
+                let surface_y = shape.surface_y(rect, x);
+                let from_surface_y = shape.surface_y(rect, from.x.clamp(rect.min.x, rect.max.x));
+                let over = (reach.min.x..=reach.max.x).contains(&to.x);
+                if !over || from.y < from_surface_y || to.y > surface_y {
+                    return None;
+                }
+                Impact::Surface {
+                    point: Vec2::new(x, surface_y),
+                    normal: shape.surface_normal(rect),
+                }
+            }
+        }
+    };
+
+    let from_above = matches!(impact, Impact::Surface { normal, .. } if normal.y > 0.);
+    (!collider.one_way || from_above).then_some(impact)
+}
+
+/// The normal of the side of `rect` that the line from `from` to `to` enters
+/// it through, if it does. Lines starting inside, like those of drops running
+/// down a wall, don't enter it.
+fn entry_normal(from: Vec2, to: Vec2, rect: &Rect) -> Option<Vec2> {
+    let direction = to - from;
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::Y;
+
+    for axis in 0..2 {
+        if direction[axis] == 0. {
+            if from[axis] < rect.min[axis] || from[axis] > rect.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let to_min = (rect.min[axis] - from[axis]) / direction[axis];
+        let to_max = (rect.max[axis] - from[axis]) / direction[axis];
+        let (near, far) = (to_min.min(to_max), to_min.max(to_max));
+        if near > enter {
+            enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = -direction[axis].signum();
+        }
+        exit = exit.min(far);
+    }
+
+    (enter <= exit && (0. ..=1.).contains(&enter)).then_some(normal)
+}
+
+fn handle_impact(
+    impact: Impact,
+    rng: &mut impl Rng,
+    settings: &RainSettings,
     (rain, rain_velocity, rain_transform): (&mut Rain, &mut Velocity, &mut Transform),
 ) {
-    match collision {
-        Collision::Top | Collision::Inside => {
-            splash_against_top_side(rng, rect, (rain, rain_velocity, rain_transform));
-        }
-        Collision::Left => {
-            run_along_left_side(rng, rect, (rain_velocity, rain_transform));
+    match impact {
+        Impact::Surface { point, normal } => {
+            splash_against_surface(
+                rng,
+                settings,
+                point,
+                normal,
+                (rain, rain_velocity, rain_transform),
+            );
         }
-        _ => {
-            // Other collisions are very unlikely to happen due
-            // to the direction of the rain fall, so just scale
-            // it down to let despawn_rain() handle it.
-            rain.0 = RainState::Splashing;
-            rain_transform.scale.x = 0.;
+        Impact::Side { x, direction } => {
+            run_along_side(rng, settings, x, direction, (rain_velocity, rain_transform));
         }
     }
 }
 
-fn splash_against_top_side(
-    rng: &mut ThreadRng,
-    rect: &Rect,
+fn splash_against_surface(
+    rng: &mut impl Rng,
+    settings: &RainSettings,
+    point: Vec2,
+    normal: Vec2,
     (rain, rain_velocity, rain_transform): (&mut Rain, &mut Velocity, &mut Transform),
 ) {
-    rain.0 = RainState::Splashing;
-    rain_transform.translation.y = rect.max.y;
+    rain.state = RainState::Splashing;
+    rain_transform.translation = point.extend(rain_transform.translation.z);
     rain_transform.scale.x *= rng.gen_range(0.2..0.6);
     let splash_angle_offset = rng.gen_range(-FRAC_PI_2..FRAC_PI_2);
-    let splash_angle = FRAC_PI_2 + splash_angle_offset;
-    let splash_speed = SPEED * rng.gen_range(0.1..0.4) * (0.3 + splash_angle_offset.abs());
-    rain_transform.rotate_local_z(splash_angle - ANGLE);
+    let splash_angle = normal.y.atan2(normal.x) + splash_angle_offset;
+    let splash_speed = settings.speed
+        * rain.kind.splash()
+        * rng.gen_range(0.1..0.4)
+        * (0.3 + splash_angle_offset.abs());
+    rain_transform.rotation = Quat::from_rotation_z(splash_angle);
     rain_velocity.0 = Vec2::from_angle(splash_angle) * splash_speed;
 }
 
-fn run_along_left_side(
-    rng: &mut ThreadRng,
-    rect: &Rect,
+fn run_along_side(
+    rng: &mut impl Rng,
+    settings: &RainSettings,
+    x: f32,
+    direction: f32,
     (rain_velocity, rain_transform): (&mut Velocity, &mut Transform),
 ) {
-    rain_transform.translation.x = rect.min.x;
+    rain_transform.translation.x = x;
     rain_transform.scale.x *= rng.gen_range(0.7..0.9);
-    let splash_angle = PI * 1.5 - rng.gen_range(0.0..0.03);
-    let splash_speed = SPEED * rng.gen_range(0.4..0.8);
+    let splash_angle = PI * 1.5 + direction * rng.gen_range(0.0..0.03);
+    let splash_speed = settings.speed * rng.gen_range(0.4..0.8);
     rain_transform.rotation = Quat::from_rotation_z(splash_angle);
     rain_velocity.0 = Vec2::from_angle(splash_angle) * splash_speed;
 }
@@ -208,3 +474,105 @@ fn despawn_rain(mut commands: Commands, rain_query: Query<Entity, With<Rain>>) {
         commands.entity(entity).despawn_recursive();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The left shelter roof of the first level.
+    fn roof() -> (Collider, Rect) {
+        let rect = Rect::new(-700., -100., -400., 150.);
+        (Collider::from_size(rect.size()), rect)
+    }
+
+    #[test]
+    fn falling_onto_the_top_splashes() {
+        let (collider, rect) = roof();
+        let impact = impact(
+            &collider,
+            &rect,
+            Vec2::new(-550., 170.),
+            Vec2::new(-550., 140.),
+        );
+        assert!(matches!(
+            impact,
+            Some(Impact::Surface { point, normal })
+                if point == Vec2::new(-550., 150.) && normal == Vec2::Y
+        ));
+    }
+
+    #[test]
+    fn blowing_into_a_wall_runs_down_it() {
+        let (collider, rect) = roof();
+        let impact = impact(
+            &collider,
+            &rect,
+            Vec2::new(-720., 0.),
+            Vec2::new(-702., -5.),
+        );
+        assert!(matches!(
+            impact,
+            Some(Impact::Side { x, direction }) if x == -700. && direction == -1.
+        ));
+    }
+
+    #[test]
+    fn running_down_a_wall_does_not_hit_it_again() {
+        let (collider, rect) = roof();
+        let to = Vec2::new(-700., 50.);
+        let from = to - Vec2::from_angle(PI * 1.5 - 0.02) * 480. / 60.;
+        assert!(impact(&collider, &rect, from, to).is_none());
+    }
+
+    #[test]
+    fn falling_onto_the_top_splashes_whichever_tick_it_lands_in() {
+        let (collider, rect) = roof();
+        let step = Vec2::new(0., -800. / 60.);
+        for offset in 0..14 {
+            let mut to = Vec2::new(-550., 200. + offset as f32);
+            let impact = (0..10).find_map(|_| {
+                let from = to;
+                to += step;
+                impact(&collider, &rect, from, to)
+            });
+            assert!(
+                matches!(
+                    impact,
+                    Some(Impact::Surface { point, normal })
+                        if point == Vec2::new(-550., 150.) && normal == Vec2::Y
+                ),
+                "drop starting {offset} higher missed the roof"
+            );
+        }
+    }
+
+    #[test]
+    fn falling_onto_a_slope_splashes() {
+        let rect = Rect::new(0., 0., 100., 100.);
+        let collider = Collider::from_size(rect.size()).with_shape(ColliderShape::SlopeUpRight);
+        let step = Vec2::new(0., -800. / 60.);
+        let mut to = Vec2::new(50., 120.);
+        let impact = (0..10).find_map(|_| {
+            let from = to;
+            to += step;
+            impact(&collider, &rect, from, to)
+        });
+        assert!(matches!(
+            impact,
+            Some(Impact::Surface { point, normal })
+                if point == Vec2::new(50., 50.) && normal.x < 0. && normal.y > 0.
+        ));
+    }
+
+    #[test]
+    fn passing_by_misses() {
+        let (collider, rect) = roof();
+        let impact = impact(
+            &collider,
+            &rect,
+            Vec2::new(-800., 0.),
+            Vec2::new(-790., -20.),
+        );
+        assert!(impact.is_none());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::app_state::*;
+use crate::levels::{CurrentLevel, LevelLayout};
+use crate::loading::LevelAssets;
+use crate::persistence::*;
+use crate::rain::RainHit;
+use crate::shield::Shield;
+use crate::ui::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .init_persistent_resource::<HighScores>()
+            .add_systems(OnEnter(AppState::InGame), spawn_score_display)
+            .add_systems(OnExit(AppState::InGame), despawn_score_display)
+            .add_systems(OnEnter(GameState::GameOver), record_high_score)
+            .add_systems(OnEnter(GameState::LevelComplete), record_high_score)
+            .add_systems(OnExit(AppState::InGame), reset_score)
+            .add_systems(OnExit(GameState::GameOver), reset_score)
+            .add_systems(OnExit(GameState::LevelComplete), reset_score)
+            .add_systems(OnExit(GameState::Restarting), reset_score)
+            .add_systems(
+                Update,
+                (tick_run_clock, count_blocked_rain, update_score_display)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// Tracks how well the current run is going.
+#[derive(Resource, Default)]
+pub struct Score {
+    pub seconds: f32,
+    pub blocked: u32,
+    pub power_spent: f32,
+}
+
+impl Score {
+    const POINTS_PER_SECOND: f32 = 10.;
+    const POINTS_PER_EFFICIENCY: f32 = 5.;
+
+    /// Drops blocked by the shield per full bar of power spent on it.
+    pub fn shield_efficiency(&self) -> f32 {
+        if self.power_spent > 0. {
+            self.blocked as f32 / self.power_spent
+        } else {
+            0.
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        (self.seconds * Self::POINTS_PER_SECOND
+            + self.shield_efficiency() * Self::POINTS_PER_EFFICIENCY) as u32
+    }
+}
+
+/// The best scores for each level, by level name, highest first.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct HighScores(BTreeMap<String, Vec<u32>>);
+
+impl Persistent for HighScores {
+    const KEY: &'static str = "high_scores";
+    const VERSION: u32 = 1;
+}
+
+impl HighScores {
+    const PER_LEVEL: usize = 5;
+
+    pub fn table(&self, level: &str) -> &[u32] {
+        self.0.get(level).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn best(&self, level: &str) -> Option<u32> {
+        self.table(level).first().copied()
+    }
+
+    fn record(&mut self, level: &str, points: u32) {
+        let table = self.0.entry(level.to_string()).or_default();
+        let index = table.partition_point(|&other| other >= points);
+        table.insert(index, points);
+        table.truncate(Self::PER_LEVEL);
+    }
+}
+
+#[derive(Component)]
+struct ScoreDisplay;
+
+fn spawn_score_display(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::from_style(score_text_style()),
+            TextSection::new("\n", score_text_style()),
+            TextSection::from_style(score_text_style()),
+        ])
+        .with_text_alignment(TextAlignment::Right)
+        .with_style(Style {
+            top: Val::Px(10.0),
+            right: Val::Px(120.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        }),
+        ScoreDisplay,
+    ));
+}
+
+fn score_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 25.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+        ..default()
+    }
+}
+
+fn despawn_score_display(mut commands: Commands, query: Query<Entity, With<ScoreDisplay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_score_display(mut query: Query<&mut Text, With<ScoreDisplay>>, score: Res<Score>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Time {:.1}", score.seconds);
+        text.sections[2].value = format!("Score {}", score.points());
+    }
+}
+
+/// Spawns the final score of a run followed by the high score table of the level,
+/// for use on the screens shown after a run.
+pub fn spawn_score_summary(children: &mut ChildBuilder, score: &Score, table: &[u32]) {
+    children.spawn((
+        MenuButtonLabelBundle::from_text(&format!("Score {}", score.points())).with_alpha(0.),
+        FadeIn::from_seconds(1.).with_background_alpha(0.),
+    ));
+    let mut highlighted = false;
+    for (rank, &points) in table.iter().enumerate() {
+        let is_this_run = !highlighted && points == score.points();
+        highlighted |= is_this_run;
+        let marker = if is_this_run { " <" } else { "" };
+        children.spawn((
+            MenuButtonLabelBundle::from_text(&format!("{}. {points}{marker}", rank + 1))
+                .with_small_font()
+                .with_alpha(0.),
+            FadeIn::from_seconds(1.).with_background_alpha(0.),
+        ));
+    }
+}
+
+fn tick_run_clock(time: Res<Time>, mut score: ResMut<Score>) {
+    score.seconds += time.delta_seconds();
+}
+
+fn count_blocked_rain(
+    mut rain_hit: EventReader<RainHit>,
+    shield_query: Query<(), With<Shield>>,
+    mut score: ResMut<Score>,
+) {
+    for RainHit(entity, kind) in rain_hit.read() {
+        if kind.damage() > 0 && shield_query.contains(*entity) {
+            score.blocked += 1;
+        }
+    }
+}
+
+pub fn record_high_score(
+    score: Res<Score>,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    layouts: Res<Assets<LevelLayout>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(layout) = current_level.layout(&level_assets, &layouts) else {
+        return;
+    };
+    high_scores.record(&layout.name, score.points());
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_kept_highest_first() {
+        let mut high_scores = HighScores::default();
+        for points in [300, 500, 100, 500] {
+            high_scores.record("Shelter", points);
+        }
+        assert_eq!(high_scores.table("Shelter"), &[500, 500, 300, 100]);
+        assert_eq!(high_scores.best("Shelter"), Some(500));
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept() {
+        let mut high_scores = HighScores::default();
+        for points in [10, 60, 20, 50, 30, 40, 5] {
+            high_scores.record("Shelter", points);
+        }
+        assert_eq!(high_scores.table("Shelter"), &[60, 50, 40, 30, 20]);
+    }
+
+    #[test]
+    fn levels_have_separate_tables() {
+        let mut high_scores = HighScores::default();
+        high_scores.record("Shelter", 100);
+        assert_eq!(high_scores.table("Stilts"), &[] as &[u32]);
+        assert_eq!(high_scores.best("Stilts"), None);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::persistence::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_persistent_resource::<AudioSettings>();
+    }
+}
+
+/// How loud the game is, each volume from 0 to 1.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    /// Sound effects, including the rain.
+    pub sfx: f32,
+    /// Whether to go quiet while the window is in the background.
+    pub mute_on_focus_loss: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.,
+            music: 0.8,
+            sfx: 1.,
+            mute_on_focus_loss: true,
+        }
+    }
+}
+
+impl Persistent for AudioSettings {
+    const KEY: &'static str = "audio_settings";
+    const VERSION: u32 = 1;
+}
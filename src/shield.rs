@@ -1,8 +1,11 @@
+use crate::actions::Actions;
 use crate::app_state::GameState;
-use crate::collider::Collider;
+use crate::collider::*;
 use crate::color::*;
+use crate::drops::DropKind;
 use crate::power::Power;
 use crate::rain::*;
+use crate::score::Score;
 use bevy::prelude::*;
 
 pub struct ShieldPlugin;
@@ -10,12 +13,15 @@ pub struct ShieldPlugin;
 impl Plugin for ShieldPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
+            FixedUpdate,
+            activate_shield
+                .before(splash_rain)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
             Update,
-            (
-                activate_shield,
-                fade_out_damage.before(get_hit_by_rain),
-                get_hit_by_rain.after(splash_rain),
-            )
+            (fade_out_damage, get_hit_by_rain)
+                .chain()
                 .run_if(in_state(GameState::Playing)),
         );
     }
@@ -28,6 +34,8 @@ impl Shield {
     const COLOR_BASE: Color = Color::rgba(0.0, 1.0, 1.0, 0.5);
     const COLOR_HIT: Color = Color::rgba(1.0, 1.0, 1.0, 0.5);
     const SIZE: Vec2 = Vec2::splat(64.);
+    /// Power it takes to hold back a heavy drop.
+    const HEAVY_DROP_COST: f32 = 0.01;
 }
 
 #[derive(Bundle)]
@@ -51,20 +59,32 @@ impl ShieldBundle {
                 visibility: Visibility::Hidden,
                 ..default()
             },
-            collider: Collider::from_size(Shield::SIZE).with_solid(false),
+            collider: Collider::from_size(Shield::SIZE)
+                .with_solid(false)
+                .with_shape(ColliderShape::Circle)
+                .with_layers(CollisionLayers::SHIELD, CollisionLayers::RAIN),
             rain_hit_listener: RainHitListener,
             shield: Shield,
         }
     }
+
+    /// Draws the shield as a bubble, if there is a texture for it.
+    pub fn with_texture(mut self, texture: Option<Handle<Image>>) -> Self {
+        if let Some(texture) = texture {
+            self.sprite.texture = texture;
+        }
+        self
+    }
 }
 
 fn activate_shield(
     mut shield_query: Query<(&mut Collider, &mut Visibility), With<Shield>>,
     mut power: ResMut<Power>,
+    mut score: ResMut<Score>,
     time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Actions>,
 ) {
-    let shield_active = keyboard_input.pressed(KeyCode::Space);
+    let shield_active = actions.shield.pressed();
     let delta = time.delta_seconds() * 60.;
     let power_cost = 0.03 * delta;
     let power_recharge = 0.02 * delta;
@@ -72,6 +92,7 @@ fn activate_shield(
     for (mut collider, mut visibility) in shield_query.iter_mut() {
         if shield_active && power.0 >= power_cost {
             power.0 -= power_cost;
+            score.power_spent += power_cost;
             if !collider.solid || *visibility == Visibility::Hidden {
                 collider.solid = true;
                 *visibility = Visibility::Visible;
@@ -90,11 +111,23 @@ fn activate_shield(
 fn get_hit_by_rain(
     mut rain_hit: EventReader<RainHit>,
     mut shield_query: Query<(&mut Sprite, Entity), With<Shield>>,
+    mut power: ResMut<Power>,
 ) {
     for (mut shield_sprite, shield_entity) in shield_query.iter_mut() {
-        for RainHit(entity) in rain_hit.read() {
-            if shield_entity == *entity {
-                shield_sprite.color = Shield::COLOR_HIT;
+        for RainHit(entity, kind) in rain_hit.read() {
+            if shield_entity != *entity {
+                continue;
+            }
+            match kind {
+                // Soaks into the shield without any effort
+                DropKind::Water => {}
+                DropKind::Heavy => {
+                    power.0 = (power.0 - Shield::HEAVY_DROP_COST).max(0.);
+                    shield_sprite.color = Shield::COLOR_HIT;
+                }
+                DropKind::Acid | DropKind::Needle => {
+                    shield_sprite.color = Shield::COLOR_HIT;
+                }
             }
         }
     }
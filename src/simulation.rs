@@ -0,0 +1,79 @@
+use crate::app_state::GameState;
+use crate::args::arg_value;
+use crate::rain::splash_rain;
+use crate::velocity::update_position;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+/// Runs the gameplay simulation in `FixedUpdate` at a steady tick rate, so
+/// that movement and collisions don't depend on the frame rate, and smooths
+/// out the rendered positions between ticks.
+pub struct SimulationPlugin;
+
+/// Simulation ticks per second, unless given on the command line as
+/// `--tick-rate <hz>`.
+const TICK_RATE: f64 = 60.;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        let tick_rate = arg_value("--tick-rate")
+            .filter(|tick_rate: &f64| *tick_rate > 0.)
+            .unwrap_or(TICK_RATE);
+        app.insert_resource(Time::<Fixed>::from_hz(tick_rate))
+            .add_systems(PreUpdate, restore_simulated_translation)
+            .add_systems(
+                FixedUpdate,
+                record_simulated_translation
+                    .after(update_position)
+                    .after(splash_rain)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                PostUpdate,
+                interpolate_translation
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// The translation of a simulated entity after the last two ticks. Its
+/// `Transform` is placed in between them when rendering, and put back at the
+/// latest one before the simulation continues.
+#[derive(Component)]
+pub struct Interpolated {
+    previous: Vec3,
+    current: Vec3,
+}
+
+impl Interpolated {
+    pub fn new(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            current: translation,
+        }
+    }
+}
+
+fn restore_simulated_translation(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.current;
+    }
+}
+
+fn record_simulated_translation(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = interpolated.current;
+        interpolated.current = transform.translation;
+    }
+}
+
+fn interpolate_translation(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &Interpolated)>,
+) {
+    let fraction = fixed_time.overstep_percentage();
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, fraction);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::collider::*;
+use bevy::prelude::*;
+
+/// Overlaps this small are left over from rounding, and still count as touching.
+const SKIN: f32 = 0.01;
+
+/// Something that can stop a rect moved by `move_and_collide`. Circles stop
+/// it as if they were their whole box.
+#[derive(Clone, Copy, Debug)]
+pub struct Obstacle {
+    pub rect: Rect,
+    pub shape: ColliderShape,
+    pub one_way: bool,
+}
+
+impl Obstacle {
+    /// An obstacle for `collider`, where it covers `rect`.
+    pub fn from_collider(collider: &Collider, rect: Rect) -> Self {
+        Self {
+            rect,
+            shape: collider.shape,
+            one_way: collider.one_way,
+        }
+    }
+
+    /// The solid part of the obstacle below its surface at `x`.
+    fn column(&self, x: f32) -> Rect {
+        let mut column = self.rect;
+        column.max.y = self.shape.surface_y(&self.rect, x);
+        column
+    }
+
+    /// Whether the obstacle has a wall in the way of moving `distance` along
+    /// the x axis. Slopes only have one on their high side, and are climbed
+    /// from the other.
+    fn walls_x(&self, distance: f32) -> bool {
+        !self.one_way
+            && match self.shape {
+                ColliderShape::SlopeUpRight => distance < 0.,
+                ColliderShape::SlopeUpLeft => distance > 0.,
+                ColliderShape::Box | ColliderShape::Circle => true,
+            }
+    }
+}
+
+/// How far a rect got when moved by `move_and_collide`.
+#[derive(Default, Debug)]
+pub struct Movement {
+    pub offset: Vec2,
+    /// Normals of the surfaces that stopped the movement along the x and y
+    /// axes respectively.
+    pub normals: [Option<Vec2>; 2],
+}
+
+impl Movement {
+    pub fn normal_x(&self) -> Option<Vec2> {
+        self.normals[0]
+    }
+
+    pub fn normal_y(&self) -> Option<Vec2> {
+        self.normals[1]
+    }
+}
+
+/// Moves `rect` by `offset` one axis at a time, first x and then y, stopping
+/// at the first obstacle in the way on each axis. Obstacles that `rect`
+/// already overlaps are ignored so that it can move out of them, apart from
+/// slopes it was moved into horizontally, which it gets lifted on top of.
+///
+/// If nothing is hit along the y axis, `rect` is moved up to `snap_down`
+/// further down onto whatever is below it, which keeps it on downward slopes.
+pub fn move_and_collide(
+    rect: Rect,
+    offset: Vec2,
+    obstacles: &[Obstacle],
+    snap_down: f32,
+) -> Movement {
+    let mut movement = Movement::default();
+    let mut rect = rect;
+
+    let time_x = obstacles
+        .iter()
+        .filter(|obstacle| obstacle.walls_x(offset.x))
+        .filter_map(|obstacle| time_of_impact(rect, 0, offset.x, &obstacle.rect))
+        .min_by(f32::total_cmp);
+    if time_x.is_some() {
+        movement.normals[0] = Some(Vec2::new(-offset.x.signum(), 0.));
+    }
+    let offset_x = offset.x * time_x.unwrap_or(1.);
+    translate(&mut rect, &mut movement, Vec2::new(offset_x, 0.));
+
+    let climb = obstacles
+        .iter()
+        .filter(|obstacle| obstacle.shape.is_slope())
+        .filter(|obstacle| rect.max.x > obstacle.rect.min.x && rect.min.x < obstacle.rect.max.x)
+        .filter_map(|obstacle| {
+            let depth = obstacle.column(rect.center().x).max.y - rect.min.y;
+            let steepness = obstacle.rect.height() / obstacle.rect.width();
+            (depth > 0. && depth <= offset_x.abs() * steepness + SKIN).then_some(depth)
+        })
+        .max_by(f32::total_cmp);
+    if let Some(climb) = climb {
+        translate(&mut rect, &mut movement, Vec2::new(0., climb));
+    }
+
+    let mut hit_y = sweep_y(rect, offset.y, obstacles);
+    let mut offset_y = offset.y;
+    if hit_y.is_none() && snap_down > 0. {
+        let snap_y = offset.y.min(0.) - snap_down;
+        hit_y = sweep_y(rect, snap_y, obstacles);
+        if hit_y.is_some() {
+            offset_y = snap_y;
+        }
+    }
+    if let Some((time, normal)) = hit_y {
+        offset_y *= time;
+        movement.normals[1] = Some(normal);
+    }
+    translate(&mut rect, &mut movement, Vec2::new(0., offset_y));
+
+    movement
+}
+
+fn translate(rect: &mut Rect, movement: &mut Movement, offset: Vec2) {
+    rect.min += offset;
+    rect.max += offset;
+    movement.offset += offset;
+}
+
+/// The fraction of `distance` that `rect` can move along the y axis before
+/// hitting an obstacle, and the normal of what it hits.
+fn sweep_y(rect: Rect, distance: f32, obstacles: &[Obstacle]) -> Option<(f32, Vec2)> {
+    obstacles
+        .iter()
+        .filter(|obstacle| !obstacle.one_way || distance < 0.)
+        .filter_map(|obstacle| {
+            let column = obstacle.column(rect.center().x);
+            let time = time_of_impact(rect, 1, distance, &column)?;
+            let normal = if distance < 0. {
+                obstacle.shape.surface_normal(&obstacle.rect)
+            } else {
+                Vec2::NEG_Y
+            };
+            Some((time, normal))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+}
+
+/// The fraction of `distance` that `rect` can move along `axis` before
+/// touching `obstacle`, if it touches it at all.
+fn time_of_impact(rect: Rect, axis: usize, distance: f32, obstacle: &Rect) -> Option<f32> {
+    let other = 1 - axis;
+    if distance == 0.
+        || rect.max[other] <= obstacle.min[other] + SKIN
+        || rect.min[other] >= obstacle.max[other] - SKIN
+    {
+        return None;
+    }
+
+    let gap = if distance > 0. {
+        obstacle.min[axis] - rect.max[axis]
+    } else {
+        rect.min[axis] - obstacle.max[axis]
+    };
+    if gap < -SKIN {
+        return None;
+    }
+
+    let time = gap.max(0.) / distance.abs();
+    (time <= 1.).then_some(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obstacle(min: Vec2, max: Vec2, shape: ColliderShape, one_way: bool) -> Obstacle {
+        Obstacle {
+            rect: Rect::from_corners(min, max),
+            shape,
+            one_way,
+        }
+    }
+
+    /// A player sized rect with the middle of its bottom edge at `foot`.
+    fn player_at(foot: Vec2) -> Rect {
+        Rect::new(foot.x - 16., foot.y, foot.x + 16., foot.y + 32.)
+    }
+
+    #[test]
+    fn boxes_stop_walking_into_them() {
+        let wall = obstacle(
+            Vec2::new(50., 0.),
+            Vec2::new(150., 100.),
+            ColliderShape::Box,
+            false,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(16., 0.)),
+            Vec2::new(30., 0.),
+            &[wall],
+            0.,
+        );
+        assert_eq!(movement.offset, Vec2::new(18., 0.));
+        assert_eq!(movement.normal_x(), Some(Vec2::NEG_X));
+    }
+
+    #[test]
+    fn slopes_are_climbed_from_their_low_side() {
+        let ramp = obstacle(
+            Vec2::ZERO,
+            Vec2::splat(100.),
+            ColliderShape::SlopeUpRight,
+            false,
+        );
+        let movement = move_and_collide(player_at(Vec2::ZERO), Vec2::new(10., 0.), &[ramp], 0.);
+        assert_eq!(movement.offset, Vec2::new(10., 10.));
+        assert_eq!(movement.normal_x(), None);
+    }
+
+    #[test]
+    fn slopes_stop_walking_into_their_high_side() {
+        let ramp = obstacle(
+            Vec2::ZERO,
+            Vec2::splat(100.),
+            ColliderShape::SlopeUpRight,
+            false,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(116., 0.)),
+            Vec2::new(-10., 0.),
+            &[ramp],
+            0.,
+        );
+        assert_eq!(movement.offset, Vec2::ZERO);
+        assert_eq!(movement.normal_x(), Some(Vec2::X));
+    }
+
+    #[test]
+    fn falling_onto_a_slope_lands_on_its_surface() {
+        let ramp = obstacle(
+            Vec2::ZERO,
+            Vec2::splat(100.),
+            ColliderShape::SlopeUpLeft,
+            false,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(50., 80.)),
+            Vec2::new(0., -50.),
+            &[ramp],
+            0.,
+        );
+        assert!(movement.offset.abs_diff_eq(Vec2::new(0., -30.), 1e-4));
+        let normal = movement.normal_y().unwrap();
+        assert!(normal.abs_diff_eq(Vec2::ONE.normalize(), 1e-4));
+    }
+
+    #[test]
+    fn circles_are_landed_on_like_their_box() {
+        let boulder = obstacle(
+            Vec2::splat(-50.),
+            Vec2::splat(50.),
+            ColliderShape::Circle,
+            false,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(40., 60.)),
+            Vec2::new(0., -20.),
+            &[boulder],
+            0.,
+        );
+        assert_eq!(movement.offset, Vec2::new(0., -10.));
+        assert_eq!(movement.normal_y(), Some(Vec2::Y));
+    }
+
+    #[test]
+    fn one_way_blocks_are_jumped_up_through() {
+        let ledge = obstacle(
+            Vec2::new(-50., 0.),
+            Vec2::new(50., 10.),
+            ColliderShape::Box,
+            true,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(0., -40.)),
+            Vec2::new(0., 30.),
+            &[ledge],
+            0.,
+        );
+        assert_eq!(movement.offset, Vec2::new(0., 30.));
+        assert_eq!(movement.normal_y(), None);
+    }
+
+    #[test]
+    fn one_way_blocks_are_landed_on() {
+        let ledge = obstacle(
+            Vec2::new(-50., 0.),
+            Vec2::new(50., 10.),
+            ColliderShape::Box,
+            true,
+        );
+        let movement = move_and_collide(
+            player_at(Vec2::new(0., 20.)),
+            Vec2::new(0., -30.),
+            &[ledge],
+            0.,
+        );
+        assert_eq!(movement.offset, Vec2::new(0., -10.));
+        assert_eq!(movement.normal_y(), Some(Vec2::Y));
+    }
+}
@@ -47,6 +47,18 @@ impl MenuButtonBundle {
         bundle
     }
 
+    pub fn disabled() -> Self {
+        let mut bundle = Self::default();
+        let color = Color::rgb(0.1, 0.1, 0.1);
+        bundle.button.background_color = color.into();
+        bundle.colors = MenuButtonColors {
+            normal: color,
+            hovered: color,
+            pressed: color,
+        };
+        bundle
+    }
+
     pub fn with_width(mut self, width: Val) -> Self {
         self.button.style.width = width;
         self
@@ -1,14 +1,20 @@
 mod fade_in;
 mod menu_button;
+mod slider;
 
 use bevy::prelude::*;
 pub use fade_in::*;
 pub use menu_button::*;
+pub use slider::*;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((menu_button::MenuButtonPlugin, fade_in::FadeInPlugin));
+        app.add_plugins((
+            menu_button::MenuButtonPlugin,
+            fade_in::FadeInPlugin,
+            slider::SliderPlugin,
+        ));
     }
 }
@@ -0,0 +1,101 @@
+use crate::ui::MenuButtonColors;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+pub struct SliderPlugin;
+
+impl Plugin for SliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (drag_slider, fill_slider).chain());
+    }
+}
+
+/// A value from 0 to 1 that can be set by clicking or dragging along the
+/// slider.
+#[derive(Component)]
+pub struct Slider {
+    pub value: f32,
+}
+
+#[derive(Component)]
+struct SliderFill;
+
+#[derive(Bundle)]
+pub struct SliderBundle {
+    pub button: ButtonBundle,
+    cursor_position: RelativeCursorPosition,
+    slider: Slider,
+}
+
+impl SliderBundle {
+    pub fn new(value: f32) -> Self {
+        SliderBundle {
+            button: ButtonBundle {
+                style: Style {
+                    width: Val::Px(300.0),
+                    height: Val::Px(30.0),
+                    padding: UiRect::all(Val::Px(3.)),
+                    ..default()
+                },
+                background_color: MenuButtonColors::default().normal.into(),
+                ..default()
+            },
+            cursor_position: RelativeCursorPosition::default(),
+            slider: Slider {
+                value: value.clamp(0., 1.),
+            },
+        }
+    }
+}
+
+/// The part of a slider showing its value, to be spawned as its child.
+#[derive(Bundle)]
+pub struct SliderFillBundle {
+    node: NodeBundle,
+    fill: SliderFill,
+}
+
+impl Default for SliderFillBundle {
+    fn default() -> Self {
+        SliderFillBundle {
+            node: NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                background_color: MenuButtonColors::default().pressed.into(),
+                ..default()
+            },
+            fill: SliderFill,
+        }
+    }
+}
+
+fn drag_slider(mut slider_query: Query<(&Interaction, &RelativeCursorPosition, &mut Slider)>) {
+    for (interaction, cursor_position, mut slider) in slider_query.iter_mut() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor_position.normalized else {
+            continue;
+        };
+        let value = position.x.clamp(0., 1.);
+        if slider.value != value {
+            slider.value = value;
+        }
+    }
+}
+
+fn fill_slider(
+    slider_query: Query<(&Slider, &Children), Changed<Slider>>,
+    mut fill_query: Query<&mut Style, With<SliderFill>>,
+) {
+    for (slider, children) in slider_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut style) = fill_query.get_mut(*child) {
+                style.width = Val::Percent(slider.value * 100.);
+            }
+        }
+    }
+}
@@ -6,13 +6,24 @@ pub struct VelocityPlugin;
 #[derive(Component)]
 pub struct Velocity(pub Vec2);
 
+/// Marks entities that apply their own `Velocity`, e.g. to collide with the
+/// level as they move, so that `update_position` leaves them alone.
+#[derive(Component)]
+pub struct ManualPosition;
+
 impl Plugin for VelocityPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_position.run_if(in_state(GameState::Playing)));
+        app.add_systems(
+            FixedUpdate,
+            update_position.run_if(in_state(GameState::Playing)),
+        );
     }
 }
 
-pub fn update_position(time: Res<Time>, mut velocity_query: Query<(&mut Transform, &Velocity)>) {
+pub fn update_position(
+    time: Res<Time>,
+    mut velocity_query: Query<(&mut Transform, &Velocity), Without<ManualPosition>>,
+) {
     for (mut transform, velocity) in &mut velocity_query {
         transform.translation += velocity.0.extend(0.) * time.delta_seconds();
     }
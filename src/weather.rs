@@ -0,0 +1,218 @@
+use crate::app_state::*;
+use crate::rain::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Changes the rain over the course of a level, following the phases of
+/// weather in its layout.
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WeatherDirector>()
+            .add_systems(OnEnter(AppState::InGame), spawn_forecast_display)
+            .add_systems(OnExit(AppState::InGame), despawn_forecast_display)
+            .add_systems(
+                FixedUpdate,
+                direct_weather
+                    .before(spawn_rain)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                update_forecast_display.run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weather {
+    Drizzle,
+    Downpour,
+    Lull,
+    Squall,
+}
+
+impl Weather {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Drizzle => "Drizzle",
+            Self::Downpour => "Downpour",
+            Self::Lull => "Lull",
+            Self::Squall => "Squall",
+        }
+    }
+
+    /// The level's usual rain, adjusted for this weather.
+    fn rain(self, base: &RainSettings) -> RainSettings {
+        let (density, speed, tilt) = match self {
+            Self::Drizzle => (0.3, 0.75, 0.),
+            Self::Downpour => (1., 1., 0.),
+            Self::Lull => (0.05, 0.7, 0.),
+            Self::Squall => (1.6, 1.25, 0.25),
+        };
+        // Scale the shares of heavy, needle and water drops
+        let drops = match self {
+            Self::Drizzle => base.drops.scaled(0., 0., 3.),
+            Self::Downpour => base.drops,
+            Self::Lull => base.drops.scaled(0., 0., 1.),
+            Self::Squall => base.drops.scaled(2., 2., 0.),
+        };
+        RainSettings {
+            density: base.density * density,
+            angle: base.angle + tilt,
+            speed: base.speed * speed,
+            drops,
+            ..base.clone()
+        }
+    }
+}
+
+/// A stretch of a level with the same weather.
+#[derive(Deserialize, Clone, Debug)]
+pub struct WeatherPhase {
+    pub weather: Weather,
+    pub seconds: f32,
+}
+
+/// Keeps track of the weather phases of the current level, which repeat once
+/// they have all passed. Levels without any phases keep their usual rain.
+#[derive(Resource, Default)]
+pub struct WeatherDirector {
+    base: RainSettings,
+    phases: Vec<WeatherPhase>,
+    elapsed: f32,
+}
+
+impl WeatherDirector {
+    /// How long before a phase starts that it's forecast, and how long the
+    /// rain then takes to change into it.
+    const TRANSITION_SECONDS: f32 = 3.;
+
+    pub fn new(base: RainSettings, phases: Vec<WeatherPhase>) -> Self {
+        let phases = phases
+            .into_iter()
+            .filter(|phase| phase.seconds > 0.)
+            .collect();
+        Self {
+            base,
+            phases,
+            elapsed: 0.,
+        }
+    }
+
+    /// The index of the current phase, and how far into it the level is.
+    fn current_phase(&self) -> Option<(usize, f32)> {
+        let cycle_seconds: f32 = self.phases.iter().map(|phase| phase.seconds).sum();
+        if cycle_seconds <= 0. {
+            return None;
+        }
+
+        let mut seconds = self.elapsed % cycle_seconds;
+        for (index, phase) in self.phases.iter().enumerate() {
+            if seconds < phase.seconds {
+                return Some((index, seconds));
+            }
+            seconds -= phase.seconds;
+        }
+        Some((self.phases.len() - 1, seconds))
+    }
+
+    fn phase(&self, index: usize) -> &WeatherPhase {
+        &self.phases[index % self.phases.len()]
+    }
+
+    /// The rain right now, easing from the previous phase into the current
+    /// one at the start of each phase.
+    fn rain(&self) -> Option<RainSettings> {
+        let (index, seconds) = self.current_phase()?;
+        let current = self.phase(index).weather.rain(&self.base);
+        // The very first phase has no previous one to ease in from
+        if self.elapsed < self.phases[0].seconds {
+            return Some(current);
+        }
+
+        let previous = self
+            .phase(index + self.phases.len() - 1)
+            .weather
+            .rain(&self.base);
+        let t = (seconds / Self::TRANSITION_SECONDS).clamp(0., 1.);
+        let t = t * t * (3. - 2. * t);
+        Some(RainSettings {
+            density: previous.density + (current.density - previous.density) * t,
+            angle: previous.angle + (current.angle - previous.angle) * t,
+            speed: previous.speed + (current.speed - previous.speed) * t,
+            drops: previous.drops.lerp(&current.drops, t),
+            ..current
+        })
+    }
+
+    pub fn current(&self) -> Option<Weather> {
+        let (index, _) = self.current_phase()?;
+        Some(self.phase(index).weather)
+    }
+
+    /// The weather that's about to begin, if it's different from the current.
+    pub fn forecast(&self) -> Option<Weather> {
+        let (index, seconds) = self.current_phase()?;
+        let current = self.phase(index);
+        let next = self.phase(index + 1);
+        (current.seconds - seconds <= Self::TRANSITION_SECONDS && next.weather != current.weather)
+            .then_some(next.weather)
+    }
+}
+
+fn direct_weather(
+    time: Res<Time>,
+    mut director: ResMut<WeatherDirector>,
+    mut settings: ResMut<RainSettings>,
+) {
+    director.elapsed += time.delta_seconds();
+    if let Some(rain) = director.rain() {
+        *settings = rain;
+    }
+}
+
+#[derive(Component)]
+struct ForecastDisplay;
+
+fn spawn_forecast_display(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 25.0,
+                color: Color::rgb(0.3, 1., 0.7),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        }),
+        ForecastDisplay,
+    ));
+}
+
+fn despawn_forecast_display(mut commands: Commands, query: Query<Entity, With<ForecastDisplay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn update_forecast_display(
+    mut query: Query<&mut Text, With<ForecastDisplay>>,
+    director: Res<WeatherDirector>,
+) {
+    let forecast = director
+        .forecast()
+        .map(|weather| format!("{} ahead", weather.name()))
+        .unwrap_or_default();
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != forecast {
+            text.sections[0].value = forecast.clone();
+        }
+    }
+}
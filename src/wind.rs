@@ -0,0 +1,79 @@
+use crate::app_state::GameState;
+use crate::rain::*;
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::Deserialize;
+
+/// Turns the rain this way and that as the wind changes.
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wind>().add_systems(
+            FixedUpdate,
+            blow_wind
+                .before(spawn_rain)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// How the wind blows in the current level.
+#[derive(Deserialize, Clone, Debug)]
+pub struct WindSettings {
+    /// The furthest the wind turns the rain from its usual angle either way,
+    /// in radians. Turning it past straight down has it blow from the right.
+    pub strength: f32,
+    /// How long the wind keeps blowing the same way.
+    pub seconds: f32,
+}
+
+impl Default for WindSettings {
+    fn default() -> Self {
+        Self {
+            strength: 0.,
+            seconds: 5.,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Wind {
+    settings: WindSettings,
+    /// How far the wind turns the rain right now.
+    angle: f32,
+    target_angle: f32,
+    seconds_until_change: f32,
+}
+
+impl Wind {
+    /// How quickly the wind turns towards its new direction, per second.
+    const TURN_RATE: f32 = 0.5;
+
+    pub fn new(settings: WindSettings) -> Self {
+        Self {
+            seconds_until_change: settings.seconds,
+            settings,
+            ..default()
+        }
+    }
+
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+}
+
+fn blow_wind(time: Res<Time>, mut wind: ResMut<Wind>, mut rain_rng: ResMut<RainRng>) {
+    if wind.settings.strength <= 0. {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    wind.seconds_until_change -= delta;
+    if wind.seconds_until_change <= 0. {
+        wind.seconds_until_change += wind.settings.seconds.max(delta);
+        let strength = wind.settings.strength;
+        wind.target_angle = rain_rng.rng().gen_range(-strength..=strength);
+    }
+    wind.angle += (wind.target_angle - wind.angle) * (Wind::TURN_RATE * delta).min(1.);
+}